@@ -0,0 +1,86 @@
+//! Bullets against invaders, every pair versus the spatial grid broadphase.
+//!
+//! The game is a binary crate, so the grid module is pulled in by path.
+
+#[path = "../src/libs/spatial.rs"]
+mod spatial;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use graphics::types::Rectangle;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use spatial::SpatialGrid;
+
+const FIELD: f64 = 2000.0;
+
+fn rects(rng: &mut StdRng, count: usize, width: f64, height: f64) -> Vec<Rectangle<f64>> {
+  (0..count)
+    .map(|_| {
+      [
+        rng.gen_range(0.0..FIELD),
+        rng.gen_range(0.0..FIELD),
+        width,
+        height,
+      ]
+    })
+    .collect()
+}
+
+fn overlaps(a: &Rectangle<f64>, b: &Rectangle<f64>) -> bool {
+  a[0] <= b[0] + b[2] && a[0] + a[2] >= b[0] && a[1] <= b[1] + b[3] && a[1] + a[3] >= b[1]
+}
+
+fn brute_force(bullets: &[Rectangle<f64>], enemies: &[Rectangle<f64>]) -> usize {
+  bullets
+    .iter()
+    .map(|bullet| {
+      enemies
+        .iter()
+        .filter(|enemy| overlaps(bullet, enemy))
+        .count()
+    })
+    .sum()
+}
+
+fn broadphase(bullets: &[Rectangle<f64>], enemies: &[Rectangle<f64>]) -> usize {
+  let mut grid = SpatialGrid::new(42.0);
+  grid.rebuild(enemies.iter().copied().enumerate());
+
+  bullets
+    .iter()
+    .map(|bullet| {
+      grid
+        .query(*bullet)
+        .into_iter()
+        .filter(|index| overlaps(bullet, &enemies[*index]))
+        .count()
+    })
+    .sum()
+}
+
+fn collisions(c: &mut Criterion) {
+  let mut group = c.benchmark_group("bullets_vs_enemies");
+
+  for count in [100, 1000, 5000] {
+    let mut rng = StdRng::seed_from_u64(42);
+    let bullets = rects(&mut rng, count, 5.0, 10.0);
+    let enemies = rects(&mut rng, count, 30.0, 22.0);
+    assert_eq!(
+      brute_force(&bullets, &enemies),
+      broadphase(&bullets, &enemies)
+    );
+
+    group.bench_with_input(BenchmarkId::new("brute_force", count), &count, |b, _| {
+      b.iter(|| brute_force(black_box(&bullets), black_box(&enemies)))
+    });
+    group.bench_with_input(BenchmarkId::new("spatial_grid", count), &count, |b, _| {
+      b.iter(|| broadphase(black_box(&bullets), black_box(&enemies)))
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, collisions);
+criterion_main!(benches);
@@ -0,0 +1,71 @@
+//! Getting a sound effect ready to play, decoding the WAV every time versus cloning it from the
+//! cache the sounds manager keeps.
+//!
+//! The game is a binary crate, so the cache module is pulled in by path, along with the asset
+//! module it logs errors through.
+
+// only the sound names and the error type are needed here, not the textures or the font
+#[allow(dead_code)]
+#[path = "../src/libs/assets.rs"]
+mod assets;
+#[path = "../src/libs/sound_cache.rs"]
+mod sound_cache;
+
+use std::path::PathBuf;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+
+use sound_cache::SoundCache;
+
+const EFFECTS: [&str; 5] = [
+  assets::PLAYER_LASER_SHOOT_SOUND_NAME,
+  assets::PLAYER_HIT_SOUND_NAME,
+  assets::ENEMY_EXPLOSION_SOUND_NAME,
+  assets::PLAYER_EXPLOSION_SOUND_NAME,
+  assets::SAUCER_SOUND_NAME,
+];
+
+fn sounds_path() -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("assets")
+    .join("sounds")
+}
+
+fn prepare(c: &mut Criterion) {
+  let path = sounds_path();
+  let mut group = c.benchmark_group("prepare_sound");
+
+  group.bench_function("from_file", |b| {
+    b.iter(|| {
+      for effect in EFFECTS {
+        black_box(
+          StaticSoundData::from_file(path.join(effect), StaticSoundSettings::default()).unwrap(),
+        );
+      }
+    })
+  });
+
+  let mut cache = SoundCache::new(path);
+  for effect in EFFECTS {
+    assert!(
+      cache.load(effect, effect).is_some(),
+      "{} didn't load",
+      effect
+    );
+  }
+
+  group.bench_function("cached", |b| {
+    b.iter(|| {
+      for effect in EFFECTS {
+        let data = cache.load(effect, effect).unwrap();
+        black_box(data.with_settings(StaticSoundSettings::new().volume(0.5)));
+      }
+    })
+  });
+
+  group.finish();
+}
+
+criterion_group!(benches, prepare);
+criterion_main!(benches);
@@ -1,12 +1,21 @@
 use cgmath::Vector2;
-use graphics::types::Matrix2d;
+use graphics::types::{Color, Matrix2d, Rectangle};
 use opengl_graphics::{GlGraphics, Texture};
-use piston::{ButtonArgs, RenderArgs, UpdateArgs};
+use piston::{ButtonArgs, ControllerAxisArgs, RenderArgs, UpdateArgs};
+
+pub const PLACEHOLDER_COLOR: Color = [1.0, 0.0, 1.0, 1.0];
 
 #[allow(unused_variables)]
 pub trait Entity {
   fn get_position(&mut self) -> Vector2<f64>;
+  /// The position at the end of the previous simulation step.
+  fn get_previous_position(&mut self) -> Vector2<f64>;
   fn get_scale(&mut self) -> Vector2<f64>;
+  fn get_size(&mut self) -> Vector2<f64>;
+  /// Drawn as a plain rectangle when the sprite couldn't be loaded.
+  fn placeholder_color(&self) -> Color {
+    PLACEHOLDER_COLOR
+  }
   fn update(&mut self, update_args: &UpdateArgs) {}
   fn render(
     &mut self,
@@ -14,22 +23,35 @@ pub trait Entity {
     texture: &Option<Texture>,
     gl: &mut GlGraphics,
     curr_trans: Matrix2d,
+    alpha: f64,
   ) {
     use graphics::*;
-    let pos = self.get_position();
+    let pos = interpolate(self.get_previous_position(), self.get_position(), alpha);
     let scale = self.get_scale();
+    let size = self.get_size();
 
-    match texture {
-      Some(tex) => image(
+    if let Some(tex) = texture {
+      image(
         tex,
         curr_trans.trans(pos.x, pos.y).scale(scale.x, scale.y),
         gl,
-      ),
-      None => {}
+      );
+    } else {
+      rectangle(
+        self.placeholder_color(),
+        [pos.x, pos.y, size.x * scale.x, size.y * scale.y],
+        curr_trans,
+        gl,
+      );
     }
   }
 }
 
+/// Where to draw something `alpha` of the way from its previous simulation step to the current one.
+pub fn interpolate(previous: Vector2<f64>, current: Vector2<f64>, alpha: f64) -> Vector2<f64> {
+  previous + (current - previous) * alpha
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Transform {
   pub x: f64,
@@ -39,6 +61,49 @@ pub struct Transform {
   pub direction: Direction,
 }
 
+impl Transform {
+  /// The bounds as a `[x, y, width, height]` rectangle.
+  pub fn rectangle(&self) -> Rectangle<f64> {
+    [self.x, self.y, self.width, self.height]
+  }
+
+  /// The smallest transform covering both, e.g. everything swept between two steps.
+  pub fn union(&self, other: &Transform) -> Transform {
+    let x = self.x.min(other.x);
+    let y = self.y.min(other.y);
+
+    Transform {
+      x,
+      y,
+      width: self.xw().max(other.xw()) - x,
+      height: self.yh().max(other.yh()) - y,
+      direction: self.direction,
+    }
+  }
+}
+
+#[cfg(test)]
+impl Transform {
+  /// A transform facing up, for laying out collisions in tests.
+  pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+    Self {
+      x,
+      y,
+      width,
+      height,
+      direction: Direction::Up,
+    }
+  }
+}
+
+/// Unwraps the time of a sweep that should have hit and checks it falls within the step.
+#[cfg(test)]
+pub fn assert_hit(time: Option<f64>) -> f64 {
+  let time = time.expect("the bullet went through without a hit");
+  assert!((0.0..=1.0).contains(&time), "hit at {}", time);
+  time
+}
+
 #[derive(Debug, Clone, Copy)]
 #[allow(dead_code)]
 pub enum Direction {
@@ -54,8 +119,6 @@ pub trait Rect {
   fn xw(&self) -> f64;
   fn yh(&self) -> f64;
   fn center_x(&self) -> f64;
-  fn center_y(&self) -> f64;
-  fn center(&self) -> Vector2<f64>;
 }
 
 impl Rect for Transform {
@@ -78,17 +141,6 @@ impl Rect for Transform {
   fn center_x(&self) -> f64 {
     self.x + self.width / 2.0
   }
-
-  fn center_y(&self) -> f64 {
-    self.y + self.height / 2.0
-  }
-
-  fn center(&self) -> Vector2<f64> {
-    Vector2 {
-      x: self.center_x(),
-      y: self.center_y(),
-    }
-  }
 }
 
 pub trait Collider {
@@ -112,6 +164,58 @@ pub trait Collider {
     false
   }
 
+  /// Moving from `previous` to where it is now, when did it first touch `collider`, as a
+  /// fraction of the move from 0 to 1. Fast movers can go straight through something thinner
+  /// than their step, this catches them. The other collider is treated as standing still.
+  fn sweep<T>(&self, previous: &Transform, collider: &T) -> Option<f64>
+  where
+    T: Collider,
+  {
+    let current = self.get_transform();
+    let target = collider.get_transform();
+
+    // grow the target by our size and sweep our top left corner through it instead
+    let axes = [
+      (
+        previous.x,
+        current.x - previous.x,
+        target.x - current.width,
+        target.xw(),
+      ),
+      (
+        previous.y,
+        current.y - previous.y,
+        target.y - current.height,
+        target.yh(),
+      ),
+    ];
+
+    let mut enter: f64 = 0.0;
+    let mut exit: f64 = 1.0;
+
+    for (start, delta, min, max) in axes {
+      if delta == 0.0 {
+        if start < min || start > max {
+          return None;
+        }
+        continue;
+      }
+
+      let (near, far) = {
+        let (a, b) = ((min - start) / delta, (max - start) / delta);
+        (a.min(b), a.max(b))
+      };
+
+      enter = enter.max(near);
+      exit = exit.min(far);
+      if enter > exit {
+        return None;
+      }
+    }
+
+    Some(enter)
+  }
+
   fn exceeds(&self, region: &Transform) -> (bool, Option<Direction>) {
     let local_transform = self.get_transform();
 
@@ -129,6 +233,62 @@ pub trait Collider {
   }
 }
 
+impl Collider for Transform {
+  fn get_transform(&self) -> &Transform {
+    self
+  }
+}
+
 pub trait Controller {
   fn on_press(&mut self, button_args: &ButtonArgs);
+  fn on_release(&mut self, button_args: &ButtonArgs);
+  fn on_axis(&mut self, axis_args: &ControllerAxisArgs);
+  fn on_focus(&mut self, focused: bool);
+  fn on_disconnect(&mut self, pad: u32);
+  fn on_text(&mut self, text: &str);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A 5×10 bullet moving between two top left corners through a 30×22 invader.
+  fn sweep(previous: (f64, f64), current: (f64, f64)) -> Option<f64> {
+    let enemy = Transform::new(100.0, 100.0, 30.0, 22.0);
+    let previous = Transform::new(previous.0, previous.1, 5.0, 10.0);
+    let current = Transform::new(current.0, current.1, 5.0, 10.0);
+
+    current.sweep(&previous, &enemy)
+  }
+
+  #[test]
+  fn catches_a_thousand_pixel_step() {
+    let time = assert_hit(sweep((112.0, 600.0), (112.0, -400.0)));
+    // the bullet's top reaches the invader's bottom edge after 478 of the 1000 pixels
+    assert!((time - 0.478).abs() < 1e-9);
+  }
+
+  #[test]
+  fn catches_a_million_pixel_step() {
+    assert_hit(sweep((112.0, 500_100.0), (112.0, -499_900.0)));
+    assert_hit(sweep((-500_000.0, 105.0), (500_000.0, 105.0)));
+  }
+
+  #[test]
+  fn catches_a_diagonal_step() {
+    let time = assert_hit(sweep((-90.0, 300.0), (310.0, -100.0)));
+    assert!(time < 0.5);
+  }
+
+  #[test]
+  fn overlapping_at_the_start_hits_straight_away() {
+    assert_eq!(sweep((112.0, 105.0), (112.0, -900.0)), Some(0.0));
+  }
+
+  #[test]
+  fn misses_targets_behind_the_start_or_past_the_end() {
+    assert_eq!(sweep((112.0, 50.0), (112.0, -950.0)), None);
+    assert_eq!(sweep((112.0, 600.0), (112.0, 300.0)), None);
+    assert_eq!(sweep((200.0, 600.0), (200.0, -400.0)), None);
+  }
 }
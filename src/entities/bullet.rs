@@ -1,12 +1,8 @@
-use std::time::Instant;
-
 use cgmath::Vector2;
+use graphics::types::Color;
 use piston::UpdateArgs;
 
-use crate::{
-  components::*,
-  libs::config::{BULLET_HEIGHT, BULLET_WIDTH},
-};
+use crate::{components::*, libs::config::config};
 
 #[derive(Debug, Clone, Copy)]
 pub enum Shooter {
@@ -17,12 +13,16 @@ pub enum Shooter {
 #[derive(Debug, Clone)]
 pub struct Bullet {
   pub transform: Transform,
+  pub previous: Transform,
   pub scale: Vector2<f64>,
   pub acceleration: Vector2<f64>,
   pub velocity: Vector2<f64>,
   pub damage: f64,
   pub owner: Shooter,
-  pub time: Instant,
+  /// Where the bullet was fired from, it follows a fixed curve from there.
+  pub origin: f64,
+  /// Game seconds since the bullet was fired.
+  pub age: f64,
 }
 
 impl Bullet {
@@ -35,28 +35,76 @@ impl Bullet {
     damage: f64,
     owner: Shooter,
   ) -> Self {
+    let size = &config().bullet;
+
+    let transform = Transform {
+      x: *x - size.width / 2.0,
+      y: *y,
+      width: size.width,
+      height: size.height,
+      direction: Direction::Up,
+    };
+
     Self {
-      transform: Transform {
-        x: *x - BULLET_WIDTH / 2.0,
-        y: *y,
-        width: BULLET_WIDTH,
-        height: BULLET_HEIGHT,
-        direction: Direction::Up,
-      },
+      transform,
+      previous: transform,
       scale,
       acceleration,
       velocity,
       damage,
       owner,
-      time: Instant::now(),
+      origin: *y,
+      age: 0.0,
     }
   }
 }
 
+#[cfg(test)]
+impl Bullet {
+  /// A shot from the player centred on `x` with its top at `y`, as [`Bullet::spawn`] fires it.
+  pub fn player_at(x: f64, y: f64) -> Self {
+    use crate::libs::config::{PLAYER_BULLET_ACCELERATION, PLAYER_BULLET_VELOCITY};
+
+    Bullet::spawn(
+      &x,
+      &y,
+      Vector2 { x: 1.0, y: 1.0 },
+      PLAYER_BULLET_ACCELERATION,
+      PLAYER_BULLET_VELOCITY,
+      100.0,
+      Shooter::Player,
+    )
+  }
+
+  /// A shot from an invader centred on `x` with its top at `y`.
+  pub fn enemy_at(x: f64, y: f64, damage: f64) -> Self {
+    use crate::libs::config::{ENEMY_BULLET_ACCELERATION, ENEMY_BULLET_VELOCITY};
+
+    Bullet::spawn(
+      &x,
+      &y,
+      Vector2 { x: 1.0, y: -1.0 },
+      ENEMY_BULLET_ACCELERATION,
+      ENEMY_BULLET_VELOCITY,
+      damage,
+      Shooter::Enemy,
+    )
+  }
+}
+
+impl Bullet {
+  /// Everything the bullet went through during the last step.
+  pub fn swept(&self) -> Transform {
+    self.previous.union(&self.transform)
+  }
+}
+
 impl Entity for Bullet {
   fn update(&mut self, update_args: &UpdateArgs) {
-    let t = self.time.elapsed().as_secs_f64();
-    self.transform.y += (0.5) * (self.acceleration.y) * t.powi(2) + self.velocity.y * t;
+    // the closed form lands on the same spot whatever the tick rate, stepping it wouldn't
+    self.age += update_args.dt;
+    let t = self.age;
+    self.transform.y = self.origin + self.velocity.y * t + 0.5 * self.acceleration.y * t.powi(2);
   }
 
   fn get_position(&mut self) -> Vector2<f64> {
@@ -66,9 +114,27 @@ impl Entity for Bullet {
     }
   }
 
+  fn get_previous_position(&mut self) -> Vector2<f64> {
+    Vector2 {
+      x: self.previous.x,
+      y: self.previous.y,
+    }
+  }
+
   fn get_scale(&mut self) -> Vector2<f64> {
     self.scale
   }
+
+  fn get_size(&mut self) -> Vector2<f64> {
+    Vector2 {
+      x: self.transform.width,
+      y: self.transform.height,
+    }
+  }
+
+  fn placeholder_color(&self) -> Color {
+    [1.0, 1.0, 1.0, 1.0]
+  }
 }
 
 impl Collider for Bullet {
@@ -76,3 +142,34 @@ impl Collider for Bullet {
     &self.transform
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn fly(tick_rate: u32, seconds: f64) -> f64 {
+    let mut bullet = Bullet::player_at(100.0, 400.0);
+    let dt = 1.0 / tick_rate as f64;
+
+    for _ in 0..(seconds * tick_rate as f64).round() as u32 {
+      bullet.update(&UpdateArgs { dt });
+    }
+
+    bullet.transform.y
+  }
+
+  #[test]
+  fn flight_does_not_depend_on_the_tick_rate() {
+    let at_60 = fly(60, 0.5);
+
+    for tick_rate in [30, 120, 240] {
+      assert!((fly(tick_rate, 0.5) - at_60).abs() < 1e-6);
+    }
+  }
+
+  #[test]
+  fn default_player_bullet_crosses_the_field_in_half_a_second() {
+    // fired from y = 400 it reaches the top in the 30 steps it took when bullets ignored dt
+    assert!(fly(60, 0.5).abs() < 1.0);
+  }
+}
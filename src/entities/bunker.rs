@@ -0,0 +1,215 @@
+use graphics::types::Matrix2d;
+use opengl_graphics::GlGraphics;
+
+use crate::components::*;
+use crate::libs::config::config;
+
+const BUNKER_COLOR: [f32; 4] = [0.13, 0.82, 0.13, 1.0];
+
+/// A shield made of small cells that bullets and invaders wear away.
+#[derive(Debug, Clone)]
+pub struct Bunker {
+  pub transform: Transform,
+  cols: usize,
+  cell_size: f64,
+  cells: Vec<bool>,
+}
+
+impl Bunker {
+  pub fn new(x: f64, y: f64) -> Self {
+    let bunker = &config().bunker;
+    let cols = bunker.cols as usize;
+    let rows = bunker.rows as usize;
+
+    let mut cells = Vec::with_capacity(cols * rows);
+    for row in 0..rows {
+      for col in 0..cols {
+        cells.push(Bunker::is_solid(col, row, cols, rows));
+      }
+    }
+
+    Self {
+      transform: Transform {
+        x,
+        y,
+        width: cols as f64 * bunker.cell_size,
+        height: rows as f64 * bunker.cell_size,
+        direction: Direction::Up,
+      },
+      cols,
+      cell_size: bunker.cell_size,
+      cells,
+    }
+  }
+
+  /// The configured number of bunkers, spread evenly between the invaders and the player.
+  pub fn spawn_row() -> Vec<Bunker> {
+    let config = config();
+    let count = config.bunker.count as usize;
+    let slot = config.window.width / count.max(1) as f64;
+    let width = config.bunker_width();
+    let height = config.bunker.rows as f64 * config.bunker.cell_size;
+    let y = config.player_init_y() - config.bunker.gap - height;
+
+    (0..count)
+      .map(|i| Bunker::new(slot * (i as f64 + 0.5) - width / 2.0, y))
+      .collect()
+  }
+
+  // the classic silhouette: chipped top corners and an arch cut out of the bottom
+  fn is_solid(col: usize, row: usize, cols: usize, rows: usize) -> bool {
+    let corner = cols / 5;
+    let chipped = row < corner && (col + row < corner || (cols - 1 - col) + row < corner);
+    let arch = row >= rows * 3 / 4 && col >= cols / 3 && col < cols - cols / 3;
+
+    !chipped && !arch
+  }
+
+  fn cell_transform(&self, index: usize) -> Transform {
+    Transform {
+      x: self.transform.x + (index % self.cols) as f64 * self.cell_size,
+      y: self.transform.y + (index / self.cols) as f64 * self.cell_size,
+      width: self.cell_size,
+      height: self.cell_size,
+      direction: Direction::Up,
+    }
+  }
+
+  /// Removes every cell the collider overlaps, returns whether anything was left to hit.
+  pub fn erode<T>(&mut self, collider: &T) -> bool
+  where
+    T: Collider,
+  {
+    if !self.collides(collider) {
+      return false;
+    }
+
+    let mut hit = false;
+    for index in 0..self.cells.len() {
+      if self.cells[index] && collider.collides(&Cell(self.cell_transform(index))) {
+        self.cells[index] = false;
+        hit = true;
+      }
+    }
+
+    hit
+  }
+
+  /// Like [`Bunker::erode`] for something that moved from `previous` since the last step, it
+  /// stops at the first solid cell on its way instead of skipping over thin walls.
+  pub fn erode_swept<T>(&mut self, previous: &Transform, collider: &T) -> bool
+  where
+    T: Collider,
+  {
+    if collider.sweep(previous, self).is_none() {
+      return false;
+    }
+
+    let impact = (0..self.cells.len())
+      .filter(|index| self.cells[*index])
+      .filter_map(|index| collider.sweep(previous, &Cell(self.cell_transform(index))))
+      .min_by(|a, b| a.total_cmp(b));
+
+    let time = match impact {
+      Some(time) => time,
+      None => return false,
+    };
+
+    let current = collider.get_transform();
+    let at_impact = Transform {
+      x: previous.x + (current.x - previous.x) * time,
+      y: previous.y + (current.y - previous.y) * time,
+      ..*current
+    };
+
+    self.erode(&Cell(at_impact))
+  }
+
+  pub fn is_destroyed(&self) -> bool {
+    !self.cells.iter().any(|cell| *cell)
+  }
+
+  pub fn render(&self, gl: &mut GlGraphics, curr_trans: Matrix2d) {
+    use graphics::*;
+
+    for (index, _) in self.cells.iter().enumerate().filter(|(_, cell)| **cell) {
+      let cell = self.cell_transform(index);
+      rectangle(
+        BUNKER_COLOR,
+        [cell.x, cell.y, cell.width, cell.height],
+        curr_trans,
+        gl,
+      );
+    }
+  }
+}
+
+impl Collider for Bunker {
+  fn get_transform(&self) -> &Transform {
+    &self.transform
+  }
+}
+
+struct Cell(Transform);
+
+impl Collider for Cell {
+  fn get_transform(&self) -> &Transform {
+    &self.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A wall one cell thick, thinner than any step of a fast bullet.
+  fn wall() -> Bunker {
+    Bunker {
+      transform: Transform::new(100.0, 200.0, 30.0, 3.0),
+      cols: 10,
+      cell_size: 3.0,
+      cells: vec![true; 10],
+    }
+  }
+
+  /// Moves a 5×10 bullet between two top left corners through the wall.
+  fn shoot(wall: &mut Bunker, previous: (f64, f64), current: (f64, f64)) -> bool {
+    let previous = Transform::new(previous.0, previous.1, 5.0, 10.0);
+    let current = Transform::new(current.0, current.1, 5.0, 10.0);
+
+    wall.erode_swept(&previous, &current)
+  }
+
+  fn cells_left(wall: &Bunker) -> usize {
+    wall.cells.iter().filter(|cell| **cell).count()
+  }
+
+  #[test]
+  fn extreme_steps_still_chip_the_wall() {
+    for (previous, current) in [
+      ((112.0, 600.0), (112.0, -400.0)),
+      ((112.0, 500_200.0), (112.0, -499_800.0)),
+      ((-88.0, 400.0), (312.0, 0.0)),
+      ((112.0, 195.0), (112.0, -900.0)),
+    ] {
+      let mut wall = wall();
+
+      assert!(shoot(&mut wall, previous, current), "{:?}", previous);
+      assert!(cells_left(&wall) < 10);
+      assert!(cells_left(&wall) > 0);
+    }
+  }
+
+  #[test]
+  fn walls_behind_the_start_or_past_the_end_are_left_alone() {
+    for (previous, current) in [
+      ((112.0, 150.0), (112.0, -850.0)),
+      ((112.0, 600.0), (112.0, 300.0)),
+    ] {
+      let mut wall = wall();
+
+      assert!(!shoot(&mut wall, previous, current));
+      assert_eq!(cells_left(&wall), 10);
+    }
+  }
+}
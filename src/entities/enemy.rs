@@ -1,59 +1,64 @@
-use std::rc::Rc;
-
 use cgmath::Vector2;
-use graphics::types::Matrix2d;
+use graphics::types::{Color, Matrix2d};
 use opengl_graphics::{GlGraphics, Texture};
 use piston::{RenderArgs, UpdateArgs};
 
 use crate::components::*;
-use crate::libs::{config::*, textures::EnemiesTextures};
+use crate::libs::arena::{Arena, EntityId};
+use crate::libs::spatial::SpatialGrid;
+use crate::libs::{config::config, textures::EnemiesTextures};
 
 #[derive(Debug, Clone)]
-struct EGS {
+struct GroupState {
   velocity: Vector2<f64>,
   zone: Transform,
 }
 
 #[derive(Debug, Clone)]
 pub struct EnemyManager {
-  pub cols: u8,
-  pub rows: u8,
-  pub entities: Vec<Enemy>,
+  pub entities: Arena<Enemy>,
   pub reached_player: bool,
-  state: EGS,
+  state: GroupState,
+  grid: SpatialGrid<EntityId>,
 }
 
 impl EnemyManager {
   pub fn new(cols: u8, rows: u8) -> Self {
-    Self {
-      cols,
-      rows,
+    let config = config();
+
+    let mut manager = Self {
       entities: EnemyManager::get_entities(cols, rows),
-      state: EGS {
+      state: GroupState {
         zone: Transform {
           x: 50.0,
           y: 0.0,
-          width: WINDOW_WIDTH - 100.0,
-          height: WINDOW_HEIGHT - 50.0,
+          width: config.window.width - 100.0,
+          height: config.window.height - 50.0,
           direction: Direction::Up,
         },
         velocity: Vector2 {
-          x: ENEMY_SPEED,
+          x: config.enemy.speed,
           y: 0.0,
         },
       },
       reached_player: false,
-    }
+      grid: SpatialGrid::new(config.enemy.width + config.enemy.spacing),
+    };
+    manager.rebuild_grid();
+
+    manager
   }
 
-  fn get_entities(c: u8, r: u8) -> Vec<Enemy> {
+  fn get_entities(c: u8, r: u8) -> Arena<Enemy> {
+    let config = config();
+    let count = c as usize * r as usize;
     let mut enemies: Vec<Enemy> = Vec::new();
     let mut curr_row: f64 = 1.0;
     let mut curr_col: f64 = 1.0;
 
     let mut kind = EnemyVariation::Squid;
 
-    for _ in 0..(c * r) as usize {
+    for _ in 0..count {
       if curr_row > 1.0 {
         kind = EnemyVariation::Crab
       }
@@ -62,10 +67,12 @@ impl EnemyManager {
         kind = EnemyVariation::Octopus
       }
 
-      if enemies.len() < (c * r) as usize {
+      if enemies.len() < count {
         enemies.push(Enemy::new(
-          curr_col * (ENEMY_WIDTH + ENEMY_SPACING) - ENEMY_SPACING + ENEMY_GRID_X,
-          curr_row * (ENEMY_HEIGHT + ENEMY_SPACING) - ENEMY_SPACING + ENEMY_GRID_Y,
+          curr_col * (config.enemy.width + config.enemy.spacing) - config.enemy.spacing
+            + config.enemy_grid_x(),
+          curr_row * (config.enemy.height + config.enemy.spacing) - config.enemy.spacing
+            + config.enemy.grid_y,
           Vector2 { x: 1.0, y: 1.0 },
           kind,
         ));
@@ -79,7 +86,7 @@ impl EnemyManager {
       curr_col += 1.0;
     }
 
-    enemies
+    enemies.into_iter().collect()
   }
 
   pub fn render(
@@ -88,60 +95,79 @@ impl EnemyManager {
     texture: &EnemiesTextures,
     gl: &mut GlGraphics,
     curr_trans: Matrix2d,
+    alpha: f64,
   ) {
-    let shared_textures = Rc::new(texture);
+    for entity in self.entities.values_mut() {
+      let texture = match entity.variation {
+        EnemyVariation::Crab => texture.crab.as_ref(),
+        EnemyVariation::Squid => texture.squid.as_ref(),
+        EnemyVariation::Octopus => texture.octopus.as_ref(),
+      };
 
-    for entity in &mut self.entities {
-      match entity.variation {
-        EnemyVariation::Crab => {
-          entity.render(gl, shared_textures.crab.as_ref().unwrap(), curr_trans)
-        }
-        EnemyVariation::Squid => {
-          entity.render(gl, shared_textures.squid.as_ref().unwrap(), curr_trans)
-        }
-        EnemyVariation::Octopus => {
-          entity.render(gl, shared_textures.octopus.as_ref().unwrap(), curr_trans)
-        }
-      }
+      entity.render(gl, texture, curr_trans, alpha);
     }
   }
 
   pub fn update(&mut self, update_args: &UpdateArgs) {
+    let config = &config().enemy;
     let mut bounced = false;
 
-    for entity in &mut self.entities {
-      let (exceeds, side) = entity.exceeds(&self.state.zone);
-
-      if exceeds && side.is_some() {
-        match side.unwrap() {
+    for entity in self.entities.values_mut() {
+      if let (true, Some(side)) = entity.exceeds(&self.state.zone) {
+        match side {
           Direction::Up => panic!("enemies exceeds their region on the wrong direction"),
           Direction::Down => {
             self.reached_player = true;
             self.state.velocity = Vector2 { x: 0.0, y: 0.0 };
           }
           Direction::Right => {
-            self.state.velocity.x = ENEMY_SPEED * -1.0;
+            self.state.velocity.x = -config.speed;
             bounced = true;
           }
           Direction::Left => {
-            self.state.velocity.x = ENEMY_SPEED * 1.0;
+            self.state.velocity.x = config.speed * 1.0;
             bounced = true;
           }
         }
       }
     }
 
-    for entity in &mut self.entities {
+    for entity in self.entities.values_mut() {
       entity.transform.x += self.state.velocity.x * update_args.dt;
 
       if bounced {
-        entity.transform.y += ENEMY_HEIGHT;
+        entity.transform.y += config.height;
       }
     }
+
+    self.rebuild_grid();
   }
 
-  pub fn remove(&mut self, index: usize) -> Enemy {
-    self.entities.remove(index)
+  fn rebuild_grid(&mut self) {
+    let entries = self
+      .entities
+      .iter()
+      .map(|(id, entity)| (id, entity.transform.rectangle()));
+    self.grid.rebuild(entries);
+  }
+
+  /// The invaders still standing that overlap `rect`.
+  pub fn query(&self, rect: &Transform) -> Vec<(EntityId, &Enemy)> {
+    self
+      .grid
+      .query(rect.rectangle())
+      .into_iter()
+      .filter(|id| self.entities.is_alive(*id))
+      .filter_map(|id| self.entities.get(id).map(|entity| (id, entity)))
+      .filter(|(_, entity)| entity.collides(rect))
+      .collect()
+  }
+
+  /// Remembers where every invader is before the next simulation step moves them.
+  pub fn snapshot(&mut self) {
+    for entity in self.entities.values_mut() {
+      entity.previous = entity.transform;
+    }
   }
 }
 
@@ -156,32 +182,48 @@ pub enum EnemyVariation {
 #[derive(Debug, Clone, Copy)]
 pub struct Enemy {
   pub transform: Transform,
+  pub previous: Transform,
   pub scale: Vector2<f64>,
   pub variation: EnemyVariation,
 }
 
 impl Enemy {
   pub fn new(x: f64, y: f64, scale: Vector2<f64>, variation: EnemyVariation) -> Self {
+    let transform = Transform {
+      x,
+      y,
+      width: config().enemy.width,
+      height: config().enemy.height,
+      direction: Direction::Up,
+    };
+
     Self {
-      transform: Transform {
-        x,
-        y,
-        width: ENEMY_WIDTH,
-        height: ENEMY_HEIGHT,
-        direction: Direction::Up,
-      },
+      transform,
+      previous: transform,
       scale,
       variation,
     }
   }
 
-  fn render(&mut self, gl: &mut GlGraphics, texture: &Texture, curr_trans: Matrix2d) {
+  fn render(
+    &mut self,
+    gl: &mut GlGraphics,
+    texture: Option<&Texture>,
+    curr_trans: Matrix2d,
+    alpha: f64,
+  ) {
     use graphics::*;
-    image(
-      texture,
-      curr_trans.trans(self.transform.x, self.transform.y),
-      gl,
-    );
+    let pos = interpolate(self.get_previous_position(), self.get_position(), alpha);
+
+    match texture {
+      Some(texture) => image(texture, curr_trans.trans(pos.x, pos.y), gl),
+      None => rectangle(
+        self.placeholder_color(),
+        [pos.x, pos.y, self.transform.width, self.transform.height],
+        curr_trans,
+        gl,
+      ),
+    }
   }
 }
 
@@ -193,9 +235,31 @@ impl Entity for Enemy {
     }
   }
 
+  fn get_previous_position(&mut self) -> Vector2<f64> {
+    Vector2 {
+      x: self.previous.x,
+      y: self.previous.y,
+    }
+  }
+
   fn get_scale(&mut self) -> Vector2<f64> {
     self.scale
   }
+
+  fn get_size(&mut self) -> Vector2<f64> {
+    Vector2 {
+      x: self.transform.width,
+      y: self.transform.height,
+    }
+  }
+
+  fn placeholder_color(&self) -> Color {
+    match self.variation {
+      EnemyVariation::Crab => [0.3, 0.9, 0.9, 1.0],
+      EnemyVariation::Squid => [0.9, 0.3, 0.9, 1.0],
+      EnemyVariation::Octopus => [0.9, 0.9, 0.3, 1.0],
+    }
+  }
 }
 
 impl Collider for Enemy {
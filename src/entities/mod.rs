@@ -1,7 +1,11 @@
 mod bullet;
+mod bunker;
 mod enemy;
 mod player;
+mod saucer;
 
 pub use bullet::*;
+pub use bunker::*;
 pub use enemy::*;
 pub use player::*;
+pub use saucer::*;
@@ -1,44 +1,162 @@
-use crate::libs::config::*;
+use crate::libs::config::config;
 use crate::*;
 
 use cgmath::Vector2;
+use graphics::types::{Color, Matrix2d};
+use opengl_graphics::Texture;
 use piston::{RenderArgs, UpdateArgs};
 
+// how many times per second the ship flickers while invulnerable
+const BLINK_RATE: f64 = 5.0;
+const LIFE_ICON_SCALE: f64 = 0.6;
+
 pub struct Player {
   pub transform: Transform,
+  pub previous: Transform,
   pub scale: Vector2<f64>,
   pub velocity: Vector2<f64>,
   pub health: Health,
+  pub lives: u32,
+  dying: f64,
+  invulnerable: f64,
 }
 
 impl Player {
   pub fn new() -> Self {
+    let config = config();
+
+    let transform = Transform {
+      x: config.player_init_x(),
+      y: config.player_init_y(),
+      width: config.player.width,
+      height: config.player.height,
+      direction: Direction::Up,
+    };
+
     Self {
-      transform: Transform {
-        x: PLAYER_INIT_X,
-        y: PLAYER_INIT_Y,
-        width: PLAYER_WIDTH,
-        height: PLAYER_HEIGHT,
-        direction: Direction::Up,
-      },
+      transform,
+      previous: transform,
       scale: Vector2 { x: 1.0, y: 1.0 },
-      velocity: PLAYER_VELOCITY,
+      velocity: config.player.velocity,
       health: Health {
         transform: Transform {
           x: 20.0,
           y: 20.0,
-          width: HEALTH_BAR_WIDTH,
-          height: HEALTH_BAR_HEIGHT,
+          width: config.health.bar_width,
+          height: config.health.bar_height,
           direction: Direction::Up,
         },
         value: 100.0,
       },
+      lives: config.lives.count,
+      dying: 0.0,
+      invulnerable: 0.0,
     }
   }
 
   pub fn is_alive(&self) -> bool {
     self.health.value > 0.0
   }
+
+  pub fn is_dying(&self) -> bool {
+    self.dying > 0.0
+  }
+
+  pub fn is_invulnerable(&self) -> bool {
+    self.invulnerable > 0.0
+  }
+
+  /// Loses a life and plays the death animation before respawning, if any life is left.
+  pub fn die(&mut self) {
+    self.lives = self.lives.saturating_sub(1);
+    self.dying = config().lives.death_duration;
+    self.transform.direction = Direction::Up;
+  }
+
+  fn respawn(&mut self) {
+    let config = config();
+
+    self.transform.x = config.player_init_x();
+    self.transform.y = config.player_init_y();
+    self.transform.direction = Direction::Up;
+    // teleporting, not moving, so there is nothing to interpolate
+    self.previous = self.transform;
+    self.health.value = 100.0;
+    self.invulnerable = config.lives.invulnerability;
+  }
+
+  /// Counts down the death and invulnerability timers, returns whether the ship just respawned.
+  pub fn tick(&mut self, dt: f64) -> bool {
+    self.invulnerable = (self.invulnerable - dt).max(0.0);
+
+    if !self.is_dying() {
+      return false;
+    }
+
+    self.dying -= dt;
+    if self.dying <= 0.0 && self.lives > 0 {
+      self.respawn();
+      return true;
+    }
+
+    false
+  }
+
+  /// Draws the ship fading out while dying and blinking while invulnerable.
+  pub fn draw(
+    &mut self,
+    renderer: &RenderArgs,
+    texture: &Option<Texture>,
+    gl: &mut GlGraphics,
+    curr_trans: Matrix2d,
+    alpha: f64,
+  ) {
+    use graphics::*;
+
+    if self.is_dying() {
+      let fade = (self.dying / config().lives.death_duration) as f32;
+
+      let color = [1.0, 0.3, 0.2, fade];
+      match texture {
+        Some(tex) => Image::new_color(color).draw(
+          tex,
+          &DrawState::default(),
+          curr_trans.trans(self.transform.x, self.transform.y),
+          gl,
+        ),
+        None => rectangle(color, self.transform.rectangle(), curr_trans, gl),
+      }
+    } else if !self.is_invulnerable() || (self.invulnerable * BLINK_RATE).fract() < 0.5 {
+      self.render(renderer, texture, gl, curr_trans, alpha);
+    }
+  }
+
+  /// One small ship per remaining life in the top right corner.
+  pub fn render_lives(&self, texture: &Option<Texture>, gl: &mut GlGraphics, curr_trans: Matrix2d) {
+    use graphics::*;
+
+    let width = self.transform.width * LIFE_ICON_SCALE;
+    let right = config().window.width - 20.0;
+
+    for i in 0..self.lives {
+      let x = right - (i + 1) as f64 * (width + 6.0);
+      match texture {
+        Some(tex) => image(
+          tex,
+          curr_trans
+            .trans(x, 20.0)
+            .scale(LIFE_ICON_SCALE, LIFE_ICON_SCALE),
+          gl,
+        ),
+        None => rectangle(
+          self.placeholder_color(),
+          [x, 20.0, width, self.transform.height * LIFE_ICON_SCALE],
+          curr_trans,
+          gl,
+        ),
+      }
+    }
+  }
 }
 
 impl Entity for Player {
@@ -49,10 +167,14 @@ impl Entity for Player {
       _ => {}
     }
 
+    // wrapping around the screen edges is a jump, it shouldn't be drawn sliding across
+    let window_width = config().window.width;
     if self.transform.center_x() < 0.0 {
-      self.transform.x = WINDOW_WIDTH - self.transform.width / 2.0
-    } else if self.transform.x() > WINDOW_WIDTH {
+      self.transform.x = window_width - self.transform.width / 2.0;
+      self.previous = self.transform;
+    } else if self.transform.x() > window_width {
       self.transform.x = self.transform.width / 2.0;
+      self.previous = self.transform;
     }
 
     self.health.transform.width = self.health.value;
@@ -65,9 +187,27 @@ impl Entity for Player {
     }
   }
 
+  fn get_previous_position(&mut self) -> Vector2<f64> {
+    Vector2 {
+      x: self.previous.x,
+      y: self.previous.y,
+    }
+  }
+
   fn get_scale(&mut self) -> Vector2<f64> {
     self.scale
   }
+
+  fn get_size(&mut self) -> Vector2<f64> {
+    Vector2 {
+      x: self.transform.width,
+      y: self.transform.height,
+    }
+  }
+
+  fn placeholder_color(&self) -> Color {
+    [0.2, 0.6, 1.0, 1.0]
+  }
 }
 
 impl Collider for Player {
@@ -90,16 +230,17 @@ impl Health {
   ) {
     use graphics::*;
 
+    let bar_config = &config().health;
     let bar_background = rectangle::rectangle_by_corners(
       self.transform.x,
       self.transform.y,
-      self.transform.x + HEALTH_BAR_WIDTH + HEALTH_BAR_STROKE,
-      self.transform.y + HEALTH_BAR_HEIGHT + HEALTH_BAR_STROKE,
+      self.transform.x + bar_config.bar_width + bar_config.bar_stroke,
+      self.transform.y + bar_config.bar_height + bar_config.bar_stroke,
     );
 
     let bar = rectangle::rectangle_by_corners(
-      self.transform.x + HEALTH_BAR_STROKE,
-      self.transform.y + HEALTH_BAR_STROKE,
+      self.transform.x + bar_config.bar_stroke,
+      self.transform.y + bar_config.bar_stroke,
       self.transform.xw(),
       self.transform.yh(),
     );
@@ -0,0 +1,93 @@
+use cgmath::Vector2;
+use graphics::types::Color;
+use piston::UpdateArgs;
+
+use crate::components::*;
+use crate::libs::config::config;
+
+/// The mystery ship that crosses the top of the screen now and then, worth a bonus when shot.
+#[derive(Debug, Clone, Copy)]
+pub struct Saucer {
+  pub transform: Transform,
+  pub previous: Transform,
+  pub scale: Vector2<f64>,
+  pub velocity: f64,
+  pub points: u64,
+}
+
+impl Saucer {
+  pub fn spawn(from_left: bool, points: u64) -> Self {
+    let config = config();
+    let saucer = &config.saucer;
+
+    let (x, direction, velocity) = if from_left {
+      (-saucer.width, Direction::Right, saucer.speed)
+    } else {
+      (config.window.width, Direction::Left, -saucer.speed)
+    };
+
+    let transform = Transform {
+      x,
+      y: saucer.y,
+      width: saucer.width,
+      height: saucer.height,
+      direction,
+    };
+
+    Self {
+      transform,
+      previous: transform,
+      scale: Vector2 { x: 1.0, y: 1.0 },
+      velocity,
+      points,
+    }
+  }
+
+  pub fn has_left_screen(&self) -> bool {
+    match self.transform.direction {
+      Direction::Right => self.transform.x() > config().window.width,
+      _ => self.transform.xw() < 0.0,
+    }
+  }
+}
+
+impl Entity for Saucer {
+  fn update(&mut self, update_args: &UpdateArgs) {
+    self.transform.x += self.velocity * update_args.dt;
+  }
+
+  fn get_position(&mut self) -> Vector2<f64> {
+    Vector2 {
+      x: self.transform.x,
+      y: self.transform.y,
+    }
+  }
+
+  fn get_previous_position(&mut self) -> Vector2<f64> {
+    Vector2 {
+      x: self.previous.x,
+      y: self.previous.y,
+    }
+  }
+
+  fn get_scale(&mut self) -> Vector2<f64> {
+    self.scale
+  }
+
+  fn get_size(&mut self) -> Vector2<f64> {
+    Vector2 {
+      x: self.transform.width,
+      y: self.transform.height,
+    }
+  }
+
+  fn placeholder_color(&self) -> Color {
+    [0.9, 0.1, 0.1, 1.0]
+  }
+}
+
+impl Collider for Saucer {
+  fn get_transform(&self) -> &Transform {
+    &self.transform
+  }
+}
@@ -0,0 +1,117 @@
+use crate::components::*;
+use crate::libs::audio::AudioBackend;
+use crate::libs::config::config;
+use crate::libs::music::Music;
+use crate::libs::sounds::SoundEffect;
+
+use super::scene::Scene;
+use super::state::{GameEvent, GameState};
+
+/// Plays what `event` sounds like at game time `now`. Works on any backend, so a recording one in
+/// the tests sees exactly what the game would have played.
+pub fn play_event(audio: &mut dyn AudioBackend, state: &GameState, event: GameEvent, now: f64) {
+  match event {
+    GameEvent::PlayerFired { x } => audio.play(SoundEffect::PlayerLaserShoot, Some(x), now),
+    GameEvent::PlayerHit { x } => audio.play(SoundEffect::PlayerHit, Some(x), now),
+    GameEvent::PlayerDied { x } => audio.play(SoundEffect::PlayerExplosion, Some(x), now),
+    GameEvent::EnemyKilled { x } => audio.play(SoundEffect::EnemyExplosion, Some(x), now),
+    GameEvent::SaucerAppeared => play_saucer(audio, state),
+    GameEvent::SaucerLeft | GameEvent::Restarted => audio.stop_loop(SoundEffect::Saucer),
+    GameEvent::Paused => {
+      audio.stop_loop(SoundEffect::Saucer);
+      audio.pause_music(true);
+      audio.play(SoundEffect::MenuSelect, None, now);
+    }
+    GameEvent::Resumed => {
+      audio.pause_music(false);
+      audio.play(SoundEffect::MenuSelect, None, now);
+      play_saucer(audio, state);
+    }
+    GameEvent::SaucerKilled { x } => {
+      audio.stop_loop(SoundEffect::Saucer);
+      audio.play(SoundEffect::SaucerExplosion, Some(x), now);
+    }
+    _ => {}
+  }
+}
+
+/// Keeps the saucer hum and the music in step with the game, once per simulation step.
+pub fn update(audio: &mut dyn AudioBackend, state: &GameState, dt: f64) {
+  if let Some(saucer) = &state.saucer {
+    audio.pan_loop(SoundEffect::Saucer, saucer.transform.center_x());
+  }
+
+  let music = match state.scene {
+    Scene::Title => Music::Title,
+    Scene::RoundIntro { .. } | Scene::Playing | Scene::Paused => Music::Gameplay,
+    Scene::GameOver => Music::GameOver,
+  };
+  audio.play_music(music);
+
+  let config = &config().enemy;
+  let enemies_total = config.grid_cols as usize * config.grid_rows as usize;
+  let enemies_left = match state.scene {
+    Scene::Playing => state.enemies.entities.len(),
+    // the march only moves while the invaders do
+    _ => 0,
+  };
+  audio.update_music(dt, enemies_left, enemies_total);
+}
+
+/// Starts the saucer hum where the saucer currently is, if there is one.
+fn play_saucer(audio: &mut dyn AudioBackend, state: &GameState) {
+  if let Some(saucer) = &state.saucer {
+    audio.play_loop(SoundEffect::Saucer, Some(saucer.transform.center_x()));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::entities::Bullet;
+  use crate::libs::audio::{PlayedEffect, RecordingAudio};
+  use crate::libs::input::InputState;
+  use crate::libs::masks::CollisionMasks;
+
+  #[test]
+  fn collisions_play_their_effects_in_order() {
+    let mut state = GameState::new(1, CollisionMasks::bundled());
+    state.scene = Scene::Playing;
+
+    let enemy = state.enemies.entities.values().next().unwrap().transform;
+    let player = state.player.transform;
+    let middle = enemy.y + enemy.height / 2.0;
+    state
+      .bullets
+      .insert(Bullet::player_at(enemy.center_x(), middle));
+    state
+      .bullets
+      .insert(Bullet::enemy_at(player.center_x(), player.y + 2.0, 5.0));
+
+    let events = state.step(1.0 / 60.0, &InputState::new());
+    let killed_at = events.iter().find_map(|event| match event {
+      GameEvent::EnemyKilled { x } => Some(*x),
+      _ => None,
+    });
+
+    let mut audio = RecordingAudio::new();
+    for &event in &events {
+      play_event(&mut audio, &state, event, 0.0);
+    }
+
+    let played = |sound_effect, x| PlayedEffect {
+      sound_effect,
+      x,
+      looping: false,
+    };
+    assert_eq!(
+      audio.played,
+      [
+        played(SoundEffect::EnemyExplosion, killed_at),
+        played(SoundEffect::PlayerHit, Some(player.center_x())),
+      ]
+    );
+    // the invader only marched a little during the step
+    assert!((killed_at.unwrap() - enemy.center_x()).abs() < 2.0);
+  }
+}
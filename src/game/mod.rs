@@ -0,0 +1,394 @@
+use std::path::{Path, PathBuf};
+
+use cgmath::Vector2;
+use opengl_graphics::{GlGraphics, GlyphCache};
+use piston::{Button, ButtonArgs, ButtonState, ControllerAxisArgs, Key, RenderArgs};
+
+use crate::components::*;
+use crate::libs::audio::AudioBackend;
+use crate::libs::clock::Clock;
+use crate::libs::config::config;
+use crate::libs::highscores::{HighScores, NameEntry};
+use crate::libs::input::{Action, ActionMapper};
+use crate::libs::masks::CollisionMasks;
+use crate::libs::settings::Settings;
+use crate::libs::sounds::SoundEffect;
+use crate::libs::textures::TextureLoader;
+use crate::libs::utils;
+
+mod audio;
+mod replay;
+mod scene;
+mod session;
+mod state;
+
+pub use replay::*;
+pub use scene::*;
+pub use session::*;
+pub use state::*;
+
+pub struct GameManager<'a> {
+  gl: GlGraphics,
+  glyphs: GlyphCache<'a>,
+  textures: TextureLoader,
+  sounds: Box<dyn AudioBackend>,
+  masks: CollisionMasks,
+  mapper: ActionMapper,
+  inputs: Vec<(Action, ButtonState)>,
+  clock: Clock,
+  record: Option<PathBuf>,
+  recorded: usize,
+  highscores: HighScores,
+  name_entry: Option<NameEntry>,
+  settings: Settings,
+
+  pub session: Session,
+}
+
+impl<'a> GameManager<'a> {
+  pub fn start(
+    gl: GlGraphics,
+    glyphs: GlyphCache<'a>,
+    assets: &Path,
+    seed: u64,
+    settings: Settings,
+    sounds: Box<dyn AudioBackend>,
+  ) -> Self {
+    let masks = CollisionMasks::load(assets);
+
+    Self {
+      gl,
+      glyphs,
+      textures: TextureLoader::new(assets),
+      sounds,
+      masks: masks.clone(),
+      mapper: ActionMapper::new(config().bindings.clone()),
+      inputs: Vec::new(),
+      clock: GameManager::clock(),
+      record: None,
+      recorded: 0,
+      highscores: HighScores::load(),
+      name_entry: None,
+      settings,
+
+      session: Session::new(seed, masks),
+    }
+  }
+
+  /// Feeds the recorded inputs back into a fresh game instead of the keyboard.
+  pub fn play_back(&mut self, replay: Replay) {
+    self.session = Session::play_back(replay, self.masks.clone());
+    self.inputs.clear();
+    self.clock = GameManager::clock();
+  }
+
+  /// Keeps the replay file at `path` up to date as inputs come in, so a crash or a killed process
+  /// still leaves everything played so far on disk.
+  pub fn record_to(&mut self, path: &Path) {
+    self.record = Some(path.to_path_buf());
+    self.recorded = self.session.replay().inputs.len();
+    self.save_replay();
+  }
+
+  fn save_replay(&self) {
+    if let Some(path) = &self.record {
+      if let Err(err) = self.session.replay().save(path) {
+        log::error!("failed to save replay to {}: {}", path.display(), err);
+      }
+    }
+  }
+
+  pub fn render(&mut self, renderer: &RenderArgs) {
+    use graphics::*;
+
+    let alpha = self.clock.alpha();
+
+    self.gl.draw(renderer.viewport(), |c, gl| {
+      clear(color::BLACK, gl);
+
+      if let Some(tex) = &self.textures.background {
+        image(tex, c.transform, gl);
+      }
+
+      let state = &mut self.session.state;
+      if state.scene.shows_world() {
+        state.player.health.render(renderer, gl, c.transform);
+        state
+          .player
+          .render_lives(&self.textures.player, gl, c.transform);
+        state
+          .player
+          .draw(renderer, &self.textures.player, gl, c.transform, alpha);
+
+        state
+          .enemies
+          .render(renderer, &self.textures.enemies, gl, c.transform, alpha);
+
+        for bunker in &state.bunkers {
+          bunker.render(gl, c.transform);
+        }
+
+        if let Some(saucer) = &mut state.saucer {
+          saucer.render(renderer, &self.textures.saucer, gl, c.transform, alpha);
+        }
+
+        for bullet in state.bullets.values_mut() {
+          bullet.render(renderer, &self.textures.bullet, gl, c.transform, alpha);
+        }
+      }
+    });
+
+    self.render_text(renderer);
+  }
+
+  fn render_text(&mut self, renderer: &RenderArgs) {
+    use graphics::*;
+
+    self.gl.draw(renderer.viewport(), |c, gl| {
+      let mut draw_text = |color, font_size, position, text| {
+        utils::draw_text(&c, gl, &mut self.glyphs, color, font_size, position, text)
+      };
+
+      match self.session.state.scene {
+        Scene::Title => {
+          draw_text(
+            color::WHITE,
+            32,
+            Vector2 { x: 20.0, y: 100.0 },
+            String::from("RUSTY INVADERS"),
+          );
+          draw_text(
+            color::WHITE,
+            16,
+            Vector2 { x: 20.0, y: 140.0 },
+            String::from("PRESS FIRE TO START"),
+          );
+        }
+        Scene::RoundIntro { round, .. } => draw_text(
+          color::WHITE,
+          32,
+          config().window_center(),
+          format!("ROUND {}", round),
+        ),
+        Scene::Paused => draw_text(
+          color::WHITE,
+          32,
+          config().window_center(),
+          String::from("PAUSED"),
+        ),
+        Scene::Playing => {}
+        Scene::GameOver => {
+          draw_text(
+            color::WHITE,
+            26,
+            Vector2 { x: 20.0, y: 60.0 },
+            format!("ROUNDS: {}", self.session.state.rounds),
+          );
+          draw_text(
+            color::WHITE,
+            26,
+            Vector2 { x: 20.0, y: 100.0 },
+            format!("SCORE: {}", self.session.state.score),
+          );
+          draw_text(
+            color::WHITE,
+            26,
+            Vector2 { x: 20.0, y: 140.0 },
+            String::from("SKILL ISSUE"),
+          );
+          draw_text(
+            color::WHITE,
+            12,
+            Vector2 { x: 20.0, y: 170.0 },
+            format!("SEED: {}", self.session.state.seed),
+          );
+
+          if let Some(entry) = &self.name_entry {
+            draw_text(
+              color::WHITE,
+              20,
+              Vector2 { x: 20.0, y: 220.0 },
+              String::from("NEW HIGH SCORE"),
+            );
+            draw_text(
+              color::WHITE,
+              20,
+              Vector2 { x: 20.0, y: 250.0 },
+              format!("INITIALS: {:_<3}", entry.name),
+            );
+          }
+
+          let table_x = config().window.width / 2.0;
+          draw_text(
+            color::WHITE,
+            20,
+            Vector2 {
+              x: table_x,
+              y: 60.0,
+            },
+            String::from("HIGH SCORES"),
+          );
+          for (rank, entry) in self.highscores.entries.iter().enumerate() {
+            draw_text(
+              color::WHITE,
+              12,
+              Vector2 {
+                x: table_x,
+                y: 90.0 + rank as f64 * 20.0,
+              },
+              format!(
+                "{:>2}. {:<3} {:>7} R{:<3} {}",
+                rank + 1,
+                entry.name,
+                entry.score,
+                entry.rounds,
+                entry.date
+              ),
+            );
+          }
+        }
+      }
+
+      if self.session.state.scene.shows_world() {
+        draw_text(
+          color::WHITE,
+          12,
+          Vector2 { x: 20.0, y: 50.0 },
+          format!("{}", self.session.state.score),
+        );
+      }
+    })
+  }
+
+  fn clock() -> Clock {
+    let simulation = &config().simulation;
+    Clock::new(simulation.tick_rate, simulation.time_scale)
+  }
+
+  /// Runs as many fixed simulation steps as fit in `real_dt` seconds of real time.
+  pub fn update(&mut self, real_dt: f64) {
+    self.clock.accumulate(real_dt);
+
+    while self.clock.next_step() {
+      self.step();
+    }
+  }
+
+  /// One fixed simulation step, replays count these rather than frames.
+  fn step(&mut self) {
+    let inputs = std::mem::take(&mut self.inputs);
+    let events = self.session.step(self.clock.dt(), &inputs);
+
+    let recorded = self.session.replay().inputs.len();
+    if recorded > self.recorded {
+      self.recorded = recorded;
+      self.save_replay();
+    }
+
+    for event in events {
+      match event {
+        GameEvent::Paused => self.clock.pause(),
+        GameEvent::Resumed => self.clock.resume(),
+        _ => {}
+      }
+      self.play(event);
+    }
+
+    audio::update(self.sounds.as_mut(), &self.session.state, self.clock.dt());
+  }
+
+  fn submit_name(&mut self) {
+    if let Some(entry) = self.name_entry.take() {
+      self
+        .highscores
+        .insert(&entry.name, entry.score, entry.rounds);
+
+      if let Err(err) = self.highscores.save() {
+        log::warn!("couldn't save the high scores: {}", err);
+      }
+    }
+  }
+
+  fn toggle_mute(&mut self) {
+    self.sounds.toggle_mute();
+    self.settings.audio = self.sounds.settings().clone();
+
+    if let Err(err) = self.settings.save() {
+      log::warn!("couldn't save the settings: {}", err);
+    }
+  }
+
+  fn apply_actions(&mut self, changes: Vec<(Action, ButtonState)>) {
+    for (action, state) in changes {
+      // muting works during a replay too, and replaying one shouldn't mute anybody
+      if action == Action::Mute {
+        if state == ButtonState::Press {
+          self.toggle_mute();
+        }
+      } else if !self.session.is_playing_back() {
+        self.inputs.push((action, state));
+      }
+    }
+  }
+
+  fn play(&mut self, event: GameEvent) {
+    if let GameEvent::GameOver { score, rounds } = event {
+      if !self.session.is_playing_back() && self.highscores.qualifies(score) {
+        self.name_entry = Some(NameEntry::new(score, rounds));
+      }
+    }
+
+    let now = self.clock.now();
+    audio::play_event(self.sounds.as_mut(), &self.session.state, event, now);
+  }
+}
+
+impl Controller for GameManager<'_> {
+  fn on_press(&mut self, button_args: &ButtonArgs) {
+    // typing initials shouldn't fire or restart the game underneath
+    if let Some(entry) = &mut self.name_entry {
+      match button_args.button {
+        Button::Keyboard(Key::Backspace) => entry.erase(),
+        Button::Keyboard(Key::Return) if entry.is_complete() => {
+          self.submit_name();
+          self
+            .sounds
+            .play(SoundEffect::MenuSelect, None, self.clock.now());
+        }
+        _ => {}
+      }
+      return;
+    }
+
+    let changes = self.mapper.on_button(button_args);
+    self.apply_actions(changes);
+  }
+
+  fn on_release(&mut self, button_args: &ButtonArgs) {
+    let changes = self.mapper.on_button(button_args);
+    self.apply_actions(changes);
+  }
+
+  fn on_axis(&mut self, axis_args: &ControllerAxisArgs) {
+    let changes = self.mapper.on_axis(axis_args);
+    self.apply_actions(changes);
+  }
+
+  fn on_focus(&mut self, focused: bool) {
+    if !focused {
+      let changes = self.mapper.release_all();
+      self.apply_actions(changes);
+    }
+  }
+
+  fn on_disconnect(&mut self, pad: u32) {
+    let changes = self.mapper.on_disconnect(pad);
+    self.apply_actions(changes);
+  }
+
+  fn on_text(&mut self, text: &str) {
+    if let Some(entry) = &mut self.name_entry {
+      entry.type_text(text);
+    }
+  }
+}
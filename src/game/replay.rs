@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use piston::ButtonState;
+use serde::{Deserialize, Serialize};
+
+use crate::libs::config::config;
+use crate::libs::input::Action;
+use crate::libs::storage::{self, Versioned};
+
+/// Bumped whenever the layout of a replay file changes.
+pub const REPLAY_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReplayInput {
+  pub tick: u64,
+  pub action: Action,
+  pub state: ButtonState,
+}
+
+/// A seed plus every action started or stopped and the update tick it was applied on, along with a fingerprint
+/// of the config the game ran under.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+  pub version: u32,
+  pub seed: u64,
+  pub config: u64,
+  pub inputs: Vec<ReplayInput>,
+}
+
+impl Versioned for Replay {
+  const VERSION: u32 = REPLAY_VERSION;
+  const NAME: &'static str = "replay";
+
+  fn version(&self) -> u32 {
+    self.version
+  }
+}
+
+impl Replay {
+  pub fn new(seed: u64) -> Self {
+    Self {
+      version: REPLAY_VERSION,
+      seed,
+      config: config().fingerprint(),
+      inputs: Vec::new(),
+    }
+  }
+
+  pub fn record(&mut self, tick: u64, action: Action, state: ButtonState) {
+    self.inputs.push(ReplayInput {
+      tick,
+      action,
+      state,
+    });
+  }
+
+  /// Action changes that came in before the given tick was simulated, in their original order.
+  pub fn inputs_at(&self, tick: u64) -> impl Iterator<Item = (Action, ButtonState)> + '_ {
+    let start = self.inputs.partition_point(|input| input.tick < tick);
+
+    self.inputs[start..]
+      .iter()
+      .take_while(move |input| input.tick == tick)
+      .map(|input| (input.action, input.state))
+  }
+
+  /// Reads a replay, refusing one recorded under a different config since it would play out
+  /// differently.
+  pub fn load(path: &Path) -> Result<Self, String> {
+    let replay: Self = storage::read(path)?;
+
+    if replay.config != config().fingerprint() {
+      return Err(String::from(
+        "recorded with a different config, run it with the same `--config`",
+      ));
+    }
+
+    Ok(replay)
+  }
+
+  pub fn save(&self, path: &Path) -> Result<(), String> {
+    storage::write(path, self)
+  }
+}
@@ -0,0 +1,133 @@
+/// How long the "ROUND N" card stays up before a wave starts moving, in seconds.
+pub const ROUND_INTRO_DURATION: f64 = 2.0;
+
+/// Where the game is at, from the title screen to the game over screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scene {
+  Title,
+  RoundIntro { round: u32, remaining: f64 },
+  Playing,
+  Paused,
+  GameOver,
+}
+
+/// Whatever can move the game from one scene to another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trigger {
+  Start,
+  TogglePause,
+  RoundCleared { round: u32 },
+  IntroFinished,
+  Lost,
+  Restart,
+}
+
+impl Scene {
+  /// The scene after `trigger`, triggers that make no sense in the current scene are ignored.
+  pub fn on(self, trigger: Trigger) -> Scene {
+    match (self, trigger) {
+      (Scene::Title, Trigger::Start) | (Scene::GameOver, Trigger::Restart) => Scene::intro(1),
+      (Scene::RoundIntro { .. }, Trigger::IntroFinished) => Scene::Playing,
+      (Scene::RoundIntro { .. } | Scene::Playing, Trigger::Lost) => Scene::GameOver,
+      (Scene::Playing, Trigger::TogglePause) => Scene::Paused,
+      (Scene::Paused, Trigger::TogglePause) => Scene::Playing,
+      (Scene::Playing, Trigger::RoundCleared { round }) => Scene::intro(round + 1),
+      (scene, _) => scene,
+    }
+  }
+
+  fn intro(round: u32) -> Scene {
+    Scene::RoundIntro {
+      round,
+      remaining: ROUND_INTRO_DURATION,
+    }
+  }
+
+  /// Whether the world is on screen, as opposed to the title or the scores.
+  pub fn shows_world(&self) -> bool {
+    matches!(
+      self,
+      Scene::RoundIntro { .. } | Scene::Playing | Scene::Paused
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn every_trigger_in_every_scene() {
+    let intro = Scene::RoundIntro {
+      round: 3,
+      remaining: 1.0,
+    };
+    let first_intro = Scene::intro(1);
+    let triggers = [
+      Trigger::Start,
+      Trigger::TogglePause,
+      Trigger::RoundCleared { round: 3 },
+      Trigger::IntroFinished,
+      Trigger::Lost,
+      Trigger::Restart,
+    ];
+
+    // the scene each trigger above leads to, in the same order
+    let table = [
+      (
+        Scene::Title,
+        [
+          first_intro,
+          Scene::Title,
+          Scene::Title,
+          Scene::Title,
+          Scene::Title,
+          Scene::Title,
+        ],
+      ),
+      (
+        intro,
+        [intro, intro, intro, Scene::Playing, Scene::GameOver, intro],
+      ),
+      (
+        Scene::Playing,
+        [
+          Scene::Playing,
+          Scene::Paused,
+          Scene::intro(4),
+          Scene::Playing,
+          Scene::GameOver,
+          Scene::Playing,
+        ],
+      ),
+      (
+        Scene::Paused,
+        [
+          Scene::Paused,
+          Scene::Playing,
+          Scene::Paused,
+          Scene::Paused,
+          Scene::Paused,
+          Scene::Paused,
+        ],
+      ),
+      (
+        Scene::GameOver,
+        [
+          Scene::GameOver,
+          Scene::GameOver,
+          Scene::GameOver,
+          Scene::GameOver,
+          Scene::GameOver,
+          first_intro,
+        ],
+      ),
+    ];
+
+    for (scene, expected) in table {
+      for (trigger, next) in triggers.into_iter().zip(expected) {
+        assert_eq!(scene.on(trigger), next, "{:?} on {:?}", scene, trigger);
+      }
+    }
+  }
+}
@@ -0,0 +1,162 @@
+use piston::ButtonState;
+
+use super::replay::Replay;
+use super::state::{GameEvent, GameState};
+use crate::libs::input::{Action, InputState};
+use crate::libs::masks::CollisionMasks;
+
+/// A game stepped tick by tick, either on live inputs that it records or on those of a replay it
+/// plays back.
+pub struct Session {
+  pub state: GameState,
+  tick: u64,
+  actions: InputState<Action>,
+  replay: Replay,
+  playback: Option<Replay>,
+}
+
+impl Session {
+  pub fn new(seed: u64, masks: CollisionMasks) -> Self {
+    Self {
+      state: GameState::new(seed, masks),
+      tick: 0,
+      actions: InputState::new(),
+      replay: Replay::new(seed),
+      playback: None,
+    }
+  }
+
+  /// A fresh game on the replay's seed that takes its inputs from the replay instead.
+  pub fn play_back(replay: Replay, masks: CollisionMasks) -> Self {
+    Self {
+      playback: Some(replay.clone()),
+      ..Session::new(replay.seed, masks)
+    }
+  }
+
+  pub fn is_playing_back(&self) -> bool {
+    self.playback.is_some()
+  }
+
+  /// Everything played so far, ready to be saved as a replay file.
+  pub fn replay(&self) -> &Replay {
+    &self.replay
+  }
+
+  /// Simulates one tick with the actions started and stopped since the last one, or with the
+  /// recorded ones when playing back.
+  pub fn step(&mut self, dt: f64, inputs: &[(Action, ButtonState)]) -> Vec<GameEvent> {
+    let inputs: Vec<(Action, ButtonState)> = match &self.playback {
+      Some(playback) => playback.inputs_at(self.tick).collect(),
+      None => inputs.to_vec(),
+    };
+
+    for (action, state) in inputs {
+      self.replay.record(self.tick, action, state);
+      self.actions.apply(action, state);
+    }
+
+    let events = self.state.step(dt, &self.actions);
+    self.actions.end_tick();
+    self.tick += 1;
+
+    events
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::REPLAY_VERSION;
+  use std::env;
+  use std::fs;
+
+  const DT: f64 = 1.0 / 60.0;
+
+  /// Taps fire every 20 ticks and holds left, then right, for 50 ticks out of every 200.
+  fn scripted(tick: u64) -> Vec<(Action, ButtonState)> {
+    let mut inputs = Vec::new();
+    match tick % 20 {
+      0 => inputs.push((Action::Fire, ButtonState::Press)),
+      1 => inputs.push((Action::Fire, ButtonState::Release)),
+      _ => {}
+    }
+    match tick % 200 {
+      50 => inputs.push((Action::MoveLeft, ButtonState::Press)),
+      100 => inputs.push((Action::MoveLeft, ButtonState::Release)),
+      150 => inputs.push((Action::MoveRight, ButtonState::Press)),
+      199 => inputs.push((Action::MoveRight, ButtonState::Release)),
+      _ => {}
+    }
+
+    inputs
+  }
+
+  /// Score, rounds and where the player, every invader and every bullet ended up.
+  fn outcome(state: &GameState) -> (u64, u32, Vec<(f64, f64)>) {
+    let mut positions = vec![(state.player.transform.x, state.player.transform.y)];
+    positions.extend(
+      state
+        .enemies
+        .entities
+        .values()
+        .map(|enemy| (enemy.transform.x, enemy.transform.y)),
+    );
+    positions.extend(
+      state
+        .bullets
+        .values()
+        .map(|bullet| (bullet.transform.x, bullet.transform.y)),
+    );
+
+    (state.score, state.rounds, positions)
+  }
+
+  #[test]
+  fn a_saved_replay_plays_back_the_same_game() {
+    let mut recorded = Session::new(7, CollisionMasks::bundled());
+    for tick in 0..600 {
+      recorded.step(DT, &scripted(tick));
+    }
+
+    let path = env::temp_dir().join(format!("rusty-invaders-replay-{}.ron", std::process::id()));
+    recorded.replay().save(&path).unwrap();
+    let loaded = Replay::load(&path);
+    fs::remove_file(&path).unwrap();
+
+    let mut played = Session::play_back(loaded.unwrap(), CollisionMasks::bundled());
+    for _ in 0..600 {
+      // live input is ignored while a replay is playing
+      played.step(DT, &[(Action::Fire, ButtonState::Press)]);
+    }
+
+    assert_eq!(played.replay().inputs, recorded.replay().inputs);
+    assert_eq!(outcome(&played.state), outcome(&recorded.state));
+  }
+
+  #[test]
+  fn replays_of_another_config_are_refused() {
+    let mut replay = Replay::new(7);
+    replay.config ^= 1;
+
+    let path = env::temp_dir().join(format!("rusty-invaders-config-{}.ron", std::process::id()));
+    replay.save(&path).unwrap();
+    let loaded = Replay::load(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert!(loaded.is_err());
+  }
+
+  #[test]
+  fn replays_of_another_version_are_refused() {
+    let mut replay = Replay::new(7);
+    replay.version = REPLAY_VERSION + 1;
+
+    let path = env::temp_dir().join(format!("rusty-invaders-future-{}.ron", std::process::id()));
+    replay.save(&path).unwrap();
+    let loaded = Replay::load(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert!(loaded.is_err());
+  }
+}
@@ -0,0 +1,750 @@
+use cgmath::Vector2;
+use piston::UpdateArgs;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::components::*;
+use crate::entities::*;
+use crate::libs::arena::{Arena, EntityId};
+use crate::libs::config::config;
+use crate::libs::input::{Action, InputState};
+use crate::libs::masks::{self, CollisionMasks, Mask};
+use crate::libs::spatial::SpatialGrid;
+
+use super::scene::{Scene, Trigger};
+
+// bullets are small and far apart, a few of them per cell keeps the buckets short
+const BULLET_GRID_CELL_SIZE: f64 = 32.0;
+
+/// Things that happened during a simulation step, consumed by the render and audio layers. `x`
+/// is the horizontal centre of where it happened, the audio pans from it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameEvent {
+  PlayerFired { x: f64 },
+  PlayerHit { x: f64 },
+  PlayerDied { x: f64 },
+  PlayerRespawned,
+  BonusLife,
+  EnemyFired,
+  EnemyKilled { x: f64 },
+  BunkerHit,
+  SaucerAppeared,
+  SaucerLeft,
+  SaucerKilled { x: f64 },
+  RoundCleared,
+  Paused,
+  Resumed,
+  GameOver { score: u64, rounds: u32 },
+  Restarted,
+}
+
+/// The game rules, free of any graphics or audio context.
+///
+/// All randomness goes through a seeded `StdRng`, so a seed and the same inputs replay the same game.
+pub struct GameState {
+  rng: StdRng,
+  events: Vec<GameEvent>,
+  masks: CollisionMasks,
+
+  pub player: Player,
+  pub bullets: Arena<Bullet>,
+  bullet_grid: SpatialGrid<EntityId>,
+  pub enemies: EnemyManager,
+  pub bunkers: Vec<Bunker>,
+  pub saucer: Option<Saucer>,
+  saucer_timer: f64,
+
+  pub seed: u64,
+  pub score: u64,
+  bonus_life_awarded: bool,
+  pub rounds: u32,
+  pub scene: Scene,
+}
+
+impl GameState {
+  pub fn new(seed: u64, masks: CollisionMasks) -> Self {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let saucer_timer = GameState::saucer_delay(&mut rng);
+
+    Self {
+      rng,
+      events: Vec::new(),
+      masks: masks.select(&config().collision),
+
+      player: Player::new(),
+      bullets: Arena::new(),
+      bullet_grid: SpatialGrid::new(BULLET_GRID_CELL_SIZE),
+      enemies: EnemyManager::new(config().enemy.grid_cols, config().enemy.grid_rows),
+      bunkers: Bunker::spawn_row(),
+      saucer: None,
+      saucer_timer,
+
+      seed,
+      score: 0,
+      bonus_life_awarded: false,
+      rounds: 0,
+      scene: Scene::Title,
+    }
+  }
+
+  /// Starts a new game on a seed drawn from the current one, keeping a whole session reproducible.
+  pub fn restart(&mut self) {
+    self.seed = self.rng.gen();
+    self.rng = StdRng::seed_from_u64(self.seed);
+    self.player = Player::new();
+    self.bullets = Arena::new();
+    self.enemies = EnemyManager::new(config().enemy.grid_cols, config().enemy.grid_rows);
+    self.bunkers = Bunker::spawn_row();
+    self.saucer = None;
+    self.saucer_timer = GameState::saucer_delay(&mut self.rng);
+    self.score = 0;
+    self.bonus_life_awarded = false;
+    self.rounds = 0;
+    self.transition(Trigger::Restart);
+    self.events.push(GameEvent::Restarted);
+  }
+
+  /// Advances the simulation by `dt` seconds with the actions as they stand for this tick.
+  pub fn step(&mut self, dt: f64, input: &InputState<Action>) -> Vec<GameEvent> {
+    self.snapshot();
+
+    match self.scene {
+      Scene::Title => {
+        if input.pressed(Action::Fire) {
+          self.transition(Trigger::Start);
+        }
+      }
+      Scene::RoundIntro { round, remaining } => {
+        if remaining > dt {
+          self.scene = Scene::RoundIntro {
+            round,
+            remaining: remaining - dt,
+          };
+        } else {
+          self.transition(Trigger::IntroFinished);
+        }
+      }
+      Scene::Playing if input.pressed(Action::Pause) => {
+        self.transition(Trigger::TogglePause);
+        self.events.push(GameEvent::Paused);
+      }
+      Scene::Playing => {
+        self.handle_input(input);
+        self.update(&UpdateArgs { dt });
+      }
+      Scene::Paused => {
+        if input.pressed(Action::Pause) {
+          self.transition(Trigger::TogglePause);
+          self.events.push(GameEvent::Resumed);
+        }
+      }
+      Scene::GameOver => {
+        if input.pressed(Action::Restart) {
+          self.restart();
+        }
+      }
+    }
+
+    std::mem::take(&mut self.events)
+  }
+
+  /// Keeps the positions before this step so rendering can interpolate towards the new ones.
+  fn snapshot(&mut self) {
+    self.player.previous = self.player.transform;
+    self.enemies.snapshot();
+
+    for bullet in self.bullets.values_mut() {
+      bullet.previous = bullet.transform;
+    }
+
+    if let Some(saucer) = &mut self.saucer {
+      saucer.previous = saucer.transform;
+    }
+  }
+
+  fn transition(&mut self, trigger: Trigger) {
+    self.scene = self.scene.on(trigger);
+  }
+
+  fn handle_input(&mut self, input: &InputState<Action>) {
+    let left = input.held(Action::MoveLeft);
+    let right = input.held(Action::MoveRight);
+
+    // the player stands still (facing up) unless exactly one arrow is held
+    self.player.transform.direction = match (left, right) {
+      _ if self.player.is_dying() => Direction::Up,
+      (true, false) => Direction::Left,
+      (false, true) => Direction::Right,
+      _ => Direction::Up,
+    };
+
+    if input.pressed(Action::Fire) {
+      self.fire();
+    }
+  }
+
+  fn fire(&mut self) {
+    let player_bullets = self.bullets.values().filter(|bullet| match bullet.owner {
+      Shooter::Player => true,
+      Shooter::Enemy => false,
+    });
+
+    let config = &config().player;
+    let max_bullets = ((self.rounds / config.bullets_frequency) + 1) as usize;
+    if player_bullets.count() < max_bullets && self.player.is_alive() {
+      self.bullets.insert(Bullet::spawn(
+        &(self.player.transform.x + self.player.transform.width / 2.0),
+        &self.player.transform.y,
+        Vector2 { x: 1.0, y: 1.0 },
+        config.bullet_acceleration,
+        config.bullet_velocity,
+        100.0,
+        Shooter::Player,
+      ));
+      self.events.push(GameEvent::PlayerFired {
+        x: self.player.transform.center_x(),
+      });
+    }
+  }
+
+  fn saucer_delay(rng: &mut StdRng) -> f64 {
+    let saucer = &config().saucer;
+    rng.gen_range(saucer.min_interval..=saucer.max_interval)
+  }
+
+  fn update_saucer(&mut self, update: &UpdateArgs) {
+    if let Some(saucer) = &mut self.saucer {
+      saucer.update(update);
+    }
+
+    if self.saucer.is_some_and(|saucer| saucer.has_left_screen()) {
+      self.saucer = None;
+      self.events.push(GameEvent::SaucerLeft);
+    }
+
+    if self.saucer.is_none() {
+      self.saucer_timer -= update.dt;
+
+      if self.saucer_timer <= 0.0 {
+        let points = &config().saucer.points;
+        let from_left = self.rng.gen_bool(0.5);
+        let bonus = points[self.rng.gen_range(0..points.len())];
+
+        self.saucer = Some(Saucer::spawn(from_left, bonus));
+        self.saucer_timer = GameState::saucer_delay(&mut self.rng);
+        self.events.push(GameEvent::SaucerAppeared);
+      }
+    }
+  }
+
+  /// A player bullet takes out the saucer or at most one invader that is still standing.
+  fn player_bullet_hits(&mut self, bullet_id: EntityId, bullet: &Bullet) {
+    if let Some(saucer) = &self.saucer {
+      let mask = self.masks.saucer.as_ref();
+      if self.bullet_hits(bullet, &saucer.transform, mask).is_some() {
+        self.score += saucer.points;
+        self.events.push(GameEvent::SaucerKilled {
+          x: saucer.transform.center_x(),
+        });
+        self.saucer = None;
+        self.bullets.despawn(bullet_id);
+        return;
+      }
+    }
+
+    // the first invader along the bullet's path takes the hit
+    let target = self
+      .enemies
+      .query(&bullet.swept())
+      .into_iter()
+      .filter_map(|(id, enemy)| {
+        let mask = self.masks.enemy(enemy.variation);
+        let time = self.bullet_hits(bullet, &enemy.transform, mask)?;
+        Some((time, id, enemy.variation, enemy.transform.center_x()))
+      })
+      .min_by(|a, b| a.0.total_cmp(&b.0))
+      .map(|(_, id, variation, x)| (id, variation, x));
+
+    if let Some((enemy_id, variation, x)) = target {
+      let config = &config().enemy;
+      let points = match variation {
+        EnemyVariation::Crab => config.crab_points,
+        EnemyVariation::Squid => config.squid_points,
+        EnemyVariation::Octopus => config.octopus_points,
+      };
+
+      self.score += points;
+      self.events.push(GameEvent::EnemyKilled { x });
+      self.enemies.entities.despawn(enemy_id);
+      self.bullets.despawn(bullet_id);
+    }
+  }
+
+  /// When during the last step the bullet first touched the target, pixel perfect for masks.
+  fn bullet_hits(&self, bullet: &Bullet, target: &Transform, mask: Option<&Mask>) -> Option<f64> {
+    masks::sweep(
+      &bullet.previous,
+      &bullet.transform,
+      self.masks.bullet.as_ref(),
+      target,
+      mask,
+    )
+  }
+
+  fn rebuild_bullet_grid(&mut self) {
+    let entries = self
+      .bullets
+      .iter()
+      .map(|(id, bullet)| (id, bullet.swept().rectangle()));
+    self.bullet_grid.rebuild(entries);
+  }
+
+  /// The bullets still in flight whose path during the last step overlaps `rect`.
+  pub fn bullets_in(&self, rect: &Transform) -> Vec<EntityId> {
+    self
+      .bullet_grid
+      .query(rect.rectangle())
+      .into_iter()
+      .filter(|id| self.bullets.is_alive(*id))
+      .filter(|id| {
+        self
+          .bullets
+          .get(*id)
+          .is_some_and(|bullet| bullet.swept().collides(rect))
+      })
+      .collect()
+  }
+
+  fn enemy_bullet_hits(&mut self, bullet_id: EntityId, bullet: &Bullet) {
+    let vulnerable = !self.player.is_dying() && !self.player.is_invulnerable();
+    let hit = self.bullet_hits(bullet, &self.player.transform, self.masks.player.as_ref());
+    if !vulnerable || hit.is_none() {
+      return;
+    }
+
+    self.player.health.value -= bullet.damage;
+    let x = self.player.transform.center_x();
+    self.events.push(GameEvent::PlayerHit { x });
+    if !self.player.is_alive() {
+      self.player.die();
+      self.events.push(GameEvent::PlayerDied { x });
+    }
+
+    self.bullets.despawn(bullet_id);
+  }
+
+  fn update(&mut self, update: &UpdateArgs) {
+    let config = config();
+    // the last death still gets its animation before the game is over
+    let out_of_lives = self.player.lives == 0 && !self.player.is_dying();
+    if out_of_lives || self.enemies.reached_player {
+      self.transition(Trigger::Lost);
+      self.events.push(GameEvent::GameOver {
+        score: self.score,
+        rounds: self.rounds,
+      });
+      if self.saucer.take().is_some() {
+        self.events.push(GameEvent::SaucerLeft);
+      }
+      return;
+    }
+
+    self.player.update(update);
+    self.enemies.update(update);
+
+    if self.player.tick(update.dt) {
+      self.events.push(GameEvent::PlayerRespawned);
+    }
+
+    self.update_saucer(update);
+
+    // rounds
+    if self.enemies.entities.is_empty() {
+      self.rounds += 1;
+      // the regen floor rises every round until it meets the ceiling
+      let regen_min = (config.health.regen_min + self.rounds as f64).min(config.health.regen_max);
+      self.player.health.value += self.rng.gen_range(regen_min..=config.health.regen_max);
+      self.enemies = EnemyManager::new(config.enemy.grid_cols, config.enemy.grid_rows);
+      if !config.bunker.persist {
+        self.bunkers = Bunker::spawn_row();
+      }
+      self.transition(Trigger::RoundCleared { round: self.rounds });
+      self.events.push(GameEvent::RoundCleared);
+    }
+
+    // difficulty i guess
+    if self.rng.gen_ratio((self.rounds + 1).min(100), 100) {
+      let random_index: usize = self.rng.gen_range(0..self.enemies.entities.len());
+      let random_enemy = self.enemies.entities.values().nth(random_index).copied();
+
+      if let Some(random_enemy) = random_enemy {
+        self.bullets.insert(Bullet::spawn(
+          &random_enemy.transform.x,
+          &random_enemy.transform.y,
+          Vector2 { x: 1.0, y: -1.0 },
+          config.enemy.bullet_acceleration,
+          config.enemy.bullet_velocity,
+          self
+            .rng
+            .gen_range(config.enemy.min_damage..config.enemy.max_damage),
+          Shooter::Enemy,
+        ));
+        self.events.push(GameEvent::EnemyFired);
+      }
+    }
+
+    // invaders plough through whatever is left of the bunkers
+    for bunker in &mut self.bunkers {
+      for (_, enemy) in self.enemies.query(&bunker.transform) {
+        bunker.erode(enemy);
+      }
+    }
+
+    for bullet in self.bullets.values_mut() {
+      bullet.update(update);
+    }
+    self.rebuild_bullet_grid();
+
+    // collision stuff, hits are queued and only despawned once every bullet has been checked
+    for index in 0..self.bunkers.len() {
+      let bunker_transform = self.bunkers[index].transform;
+      for bullet_id in self.bullets_in(&bunker_transform) {
+        let hit = match self.bullets.get(bullet_id) {
+          Some(bullet) => self.bunkers[index].erode_swept(&bullet.previous, bullet),
+          None => false,
+        };
+
+        if hit {
+          self.events.push(GameEvent::BunkerHit);
+          self.bullets.despawn(bullet_id);
+        }
+      }
+    }
+
+    for bullet_id in self.bullets.ids() {
+      let bullet = match self.bullets.get(bullet_id) {
+        Some(bullet) if self.bullets.is_alive(bullet_id) => bullet.clone(),
+        _ => continue,
+      };
+
+      match bullet.owner {
+        Shooter::Player => self.player_bullet_hits(bullet_id, &bullet),
+        Shooter::Enemy => self.enemy_bullet_hits(bullet_id, &bullet),
+      }
+    }
+
+    self.bullets.flush();
+    self.enemies.entities.flush();
+
+    self.bunkers.retain(|bunker| !bunker.is_destroyed());
+
+    let bonus_score = config.lives.bonus_score;
+    if bonus_score > 0 && !self.bonus_life_awarded && self.score >= bonus_score {
+      self.bonus_life_awarded = true;
+      self.player.lives += 1;
+      self.events.push(GameEvent::BonusLife);
+    }
+
+    // out of bounds bullets
+    self
+      .bullets
+      .retain(|bullet| bullet.transform.y < config.window.height && bullet.transform.y >= 0.0);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const DT: f64 = 1.0 / 60.0;
+
+  /// A game past the title screen and the round intro.
+  fn playing(seed: u64) -> GameState {
+    let mut state = GameState::new(seed, CollisionMasks::bundled());
+    state.scene = Scene::Playing;
+    state
+  }
+
+  #[test]
+  fn player_bullet_kills_an_invader() {
+    let mut state = playing(1);
+    let target = state.enemies.entities.values().next().unwrap().transform;
+    let enemies = state.enemies.entities.len();
+
+    // the sprite's edges are see-through, so aim for the middle
+    let middle = target.y + target.height / 2.0;
+    state
+      .bullets
+      .insert(Bullet::player_at(target.center_x(), middle));
+
+    let events = state.step(DT, &InputState::new());
+
+    assert!(events
+      .iter()
+      .any(|event| matches!(event, GameEvent::EnemyKilled { .. })));
+    assert_eq!(state.enemies.entities.len(), enemies - 1);
+    assert!(state.score > 0);
+  }
+
+  #[test]
+  fn a_fast_bullet_cannot_tunnel_through_an_invader() {
+    let mut state = playing(1);
+    let target = state.enemies.entities.values().next().unwrap().transform;
+
+    let mut bullet = Bullet::player_at(target.center_x(), target.yh() + 20.0);
+    // a thousand pixels in a single step, far more than the invader is tall
+    bullet.velocity.y = -1000.0 / DT;
+    bullet.acceleration.y = 0.0;
+    state.bullets.insert(bullet);
+
+    let events = state.step(DT, &InputState::new());
+
+    assert!(events
+      .iter()
+      .any(|event| matches!(event, GameEvent::EnemyKilled { .. })));
+  }
+
+  #[test]
+  fn two_bullets_on_one_invader_score_once() {
+    let mut state = playing(1);
+    let (enemy_id, enemy) = state.enemies.entities.iter().next().unwrap();
+    let target = enemy.transform;
+    let enemies = state.enemies.entities.len();
+
+    let bullets: Vec<_> = [target.x + 4.0, target.xw() - 4.0]
+      .iter()
+      .map(|x| {
+        state
+          .bullets
+          .insert(Bullet::player_at(*x, target.yh() - 2.0))
+      })
+      .collect();
+
+    let events = state.step(DT, &InputState::new());
+
+    let kills = events
+      .iter()
+      .filter(|event| matches!(event, GameEvent::EnemyKilled { .. }))
+      .count();
+    assert_eq!(kills, 1);
+    assert_eq!(state.score, config().enemy.squid_points);
+    assert!(state.enemies.entities.get(enemy_id).is_none());
+    assert_eq!(state.enemies.entities.len(), enemies - 1);
+    let left = bullets
+      .iter()
+      .filter(|id| state.bullets.get(**id).is_some())
+      .count();
+    assert_eq!(left, 1);
+  }
+
+  #[test]
+  fn enemy_bullet_hits_the_player() {
+    let mut state = playing(1);
+    let player = state.player.transform;
+
+    state
+      .bullets
+      .insert(Bullet::enemy_at(player.center_x(), player.y + 2.0, 5.0));
+
+    let events = state.step(DT, &InputState::new());
+
+    assert!(events
+      .iter()
+      .any(|event| matches!(event, GameEvent::PlayerHit { .. })));
+    assert_eq!(state.player.health.value, 95.0);
+  }
+
+  #[test]
+  fn nothing_moves_while_paused() {
+    let mut state = playing(1);
+    state.scene = Scene::Paused;
+    let player = state.player.transform;
+    state
+      .bullets
+      .insert(Bullet::player_at(player.center_x(), player.y - 100.0));
+
+    let positions = |state: &GameState| {
+      let bullets = state.bullets.values().map(|bullet| bullet.transform);
+      let enemies = state.enemies.entities.values().map(|enemy| enemy.transform);
+      bullets
+        .chain(enemies)
+        .map(|transform| (transform.x, transform.y))
+        .collect::<Vec<_>>()
+    };
+    let before = positions(&state);
+
+    for _ in 0..60 {
+      state.step(DT, &InputState::new());
+    }
+
+    assert_eq!(state.scene, Scene::Paused);
+    assert_eq!(positions(&state), before);
+  }
+
+  /// Shoots the player with a bullet strong enough to take the whole health bar.
+  fn kill_player(state: &mut GameState) -> Vec<GameEvent> {
+    let player = state.player.transform;
+    state
+      .bullets
+      .insert(Bullet::enemy_at(player.center_x(), player.y + 2.0, 1000.0));
+
+    state.step(DT, &InputState::new())
+  }
+
+  /// Steps until the death animation is over, returning every event on the way.
+  fn wait_out_death(state: &mut GameState) -> Vec<GameEvent> {
+    let ticks = (config().lives.death_duration / DT).ceil() as u32 + 1;
+
+    (0..ticks)
+      .flat_map(|_| state.step(DT, &InputState::new()))
+      .collect()
+  }
+
+  #[test]
+  fn dying_costs_a_life() {
+    let mut state = playing(1);
+    let lives = state.player.lives;
+
+    let events = kill_player(&mut state);
+
+    assert!(events
+      .iter()
+      .any(|event| matches!(event, GameEvent::PlayerDied { .. })));
+    assert_eq!(state.player.lives, lives - 1);
+    assert!(state.player.is_dying());
+  }
+
+  #[test]
+  fn the_ship_respawns_in_the_middle_with_full_health() {
+    let mut state = playing(1);
+    state.player.transform.x = 10.0;
+    kill_player(&mut state);
+
+    let events = wait_out_death(&mut state);
+
+    assert!(events.contains(&GameEvent::PlayerRespawned));
+    assert_eq!(state.player.transform.x, config().player_init_x());
+    assert_eq!(state.player.health.value, 100.0);
+  }
+
+  #[test]
+  fn enemy_bullets_pass_through_an_invulnerable_ship() {
+    let mut state = playing(1);
+    kill_player(&mut state);
+    wait_out_death(&mut state);
+    assert!(state.player.is_invulnerable());
+
+    let player = state.player.transform;
+    state
+      .bullets
+      .insert(Bullet::enemy_at(player.center_x(), player.y + 2.0, 5.0));
+    let events = state.step(DT, &InputState::new());
+
+    assert!(!events
+      .iter()
+      .any(|event| matches!(event, GameEvent::PlayerHit { .. })));
+    assert_eq!(state.player.health.value, 100.0);
+  }
+
+  #[test]
+  fn the_game_is_over_once_the_last_death_has_played_out() {
+    let mut state = playing(1);
+    state.player.lives = 1;
+
+    kill_player(&mut state);
+    state.step(DT, &InputState::new());
+    assert_eq!(state.scene, Scene::Playing);
+
+    let events = wait_out_death(&mut state);
+    assert!(!events.contains(&GameEvent::PlayerRespawned));
+    assert_eq!(state.scene, Scene::GameOver);
+  }
+
+  #[test]
+  fn the_bonus_life_is_awarded_only_once() {
+    let mut state = playing(1);
+    let lives = state.player.lives;
+    state.score = config().lives.bonus_score;
+
+    let events = state.step(DT, &InputState::new());
+    assert!(events.contains(&GameEvent::BonusLife));
+    assert_eq!(state.player.lives, lives + 1);
+
+    state.score *= 2;
+    let events = state.step(DT, &InputState::new());
+    assert!(!events.contains(&GameEvent::BonusLife));
+    assert_eq!(state.player.lives, lives + 1);
+  }
+
+  #[test]
+  fn shooting_the_saucer_awards_its_bonus() {
+    let mut state = playing(1);
+    let saucer = Saucer::spawn(true, 150);
+    let target = saucer.transform;
+    state.saucer = Some(saucer);
+
+    state
+      .bullets
+      .insert(Bullet::player_at(target.center_x(), target.yh() - 2.0));
+
+    let events = state.step(DT, &InputState::new());
+
+    assert!(events
+      .iter()
+      .any(|event| matches!(event, GameEvent::SaucerKilled { .. })));
+    assert_eq!(state.score, 150);
+    assert!(state.saucer.is_none());
+  }
+
+  #[test]
+  fn clearing_the_wave_starts_the_next_round() {
+    let mut state = playing(1);
+    state.enemies.entities = Arena::new();
+
+    let events = state.step(DT, &InputState::new());
+
+    assert!(events.contains(&GameEvent::RoundCleared));
+    assert_eq!(state.rounds, 1);
+    assert!(!state.enemies.entities.is_empty());
+  }
+
+  #[test]
+  fn late_rounds_keep_regenerating_and_firing() {
+    let mut state = playing(1);
+    state.rounds = 120;
+    state.enemies.entities = Arena::new();
+
+    let events = state.step(DT, &InputState::new());
+
+    assert!(events.contains(&GameEvent::RoundCleared));
+    assert_eq!(state.rounds, 121);
+  }
+
+  /// Lets the invaders shoot for `steps` ticks, then restarts, and lists where each shot came
+  /// from, how hard it hits and the seed the restart drew.
+  fn enemy_fire(seed: u64, steps: u32) -> (Vec<(f64, f64)>, u64) {
+    let mut state = playing(seed);
+    // late enough in the game that the invaders shoot most ticks
+    state.rounds = 50;
+
+    let mut shots = Vec::new();
+    for _ in 0..steps {
+      let before = state.bullets.ids();
+      state.step(DT, &InputState::new());
+      let bullets = state.bullets.iter().filter(|(id, _)| !before.contains(id));
+      shots.extend(bullets.map(|(_, bullet)| (bullet.transform.x, bullet.damage)));
+    }
+
+    state.restart();
+    (shots, state.seed)
+  }
+
+  #[test]
+  fn same_seed_plays_the_same_game() {
+    let first = enemy_fire(42, 120);
+
+    assert!(!first.0.is_empty(), "the invaders should have fired");
+    assert_eq!(first, enemy_fire(42, 120));
+    assert_ne!(first, enemy_fire(43, 120));
+  }
+}
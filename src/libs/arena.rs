@@ -0,0 +1,196 @@
+use std::mem;
+
+/// A handle to an entity that stays valid while other entities come and go. Once the entity is
+/// removed its slot gets reused under a new generation, so old handles never alias a newcomer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EntityId {
+  index: u32,
+  generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Slot<T> {
+  generation: u32,
+  value: Option<T>,
+}
+
+/// Entities stored by stable id, removals requested during a tick are queued and applied by
+/// [`Arena::flush`] once nothing is iterating anymore.
+#[derive(Debug, Clone)]
+pub struct Arena<T> {
+  slots: Vec<Slot<T>>,
+  free: Vec<u32>,
+  despawned: Vec<EntityId>,
+  len: usize,
+}
+
+impl<T> Default for Arena<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> Arena<T> {
+  pub fn new() -> Self {
+    Self {
+      slots: Vec::new(),
+      free: Vec::new(),
+      despawned: Vec::new(),
+      len: 0,
+    }
+  }
+
+  pub fn insert(&mut self, value: T) -> EntityId {
+    self.len += 1;
+
+    if let Some(index) = self.free.pop() {
+      let slot = &mut self.slots[index as usize];
+      slot.value = Some(value);
+      return EntityId {
+        index,
+        generation: slot.generation,
+      };
+    }
+
+    self.slots.push(Slot {
+      generation: 0,
+      value: Some(value),
+    });
+    EntityId {
+      index: self.slots.len() as u32 - 1,
+      generation: 0,
+    }
+  }
+
+  /// Removes the entity straight away, returns it if the id was still valid.
+  pub fn remove(&mut self, id: EntityId) -> Option<T> {
+    let slot = self.slots.get_mut(id.index as usize)?;
+    if slot.generation != id.generation {
+      return None;
+    }
+
+    let value = slot.value.take()?;
+    slot.generation = slot.generation.wrapping_add(1);
+    self.free.push(id.index);
+    self.len -= 1;
+
+    Some(value)
+  }
+
+  /// Queues the entity for removal at the next [`Arena::flush`], returns false if it was already
+  /// gone or queued, so two hits on the same target in one tick only count once.
+  pub fn despawn(&mut self, id: EntityId) -> bool {
+    if !self.is_alive(id) {
+      return false;
+    }
+
+    self.despawned.push(id);
+    true
+  }
+
+  /// Applies the queued removals.
+  pub fn flush(&mut self) {
+    for id in mem::take(&mut self.despawned) {
+      self.remove(id);
+    }
+  }
+
+  /// The entity exists and isn't waiting to be despawned.
+  pub fn is_alive(&self, id: EntityId) -> bool {
+    self.get(id).is_some() && !self.despawned.contains(&id)
+  }
+
+  pub fn get(&self, id: EntityId) -> Option<&T> {
+    let slot = self.slots.get(id.index as usize)?;
+    if slot.generation != id.generation {
+      return None;
+    }
+
+    slot.value.as_ref()
+  }
+
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Every entity with its id, in slot order, which is stable for a given sequence of changes.
+  pub fn iter(&self) -> impl Iterator<Item = (EntityId, &T)> {
+    self.slots.iter().enumerate().filter_map(|(index, slot)| {
+      let id = EntityId {
+        index: index as u32,
+        generation: slot.generation,
+      };
+      slot.value.as_ref().map(|value| (id, value))
+    })
+  }
+
+  pub fn ids(&self) -> Vec<EntityId> {
+    self.iter().map(|(id, _)| id).collect()
+  }
+
+  pub fn values(&self) -> impl Iterator<Item = &T> {
+    self.slots.iter().filter_map(|slot| slot.value.as_ref())
+  }
+
+  pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+    self.slots.iter_mut().filter_map(|slot| slot.value.as_mut())
+  }
+
+  /// Removes every entity for which `keep` returns false.
+  pub fn retain(&mut self, mut keep: impl FnMut(&T) -> bool) {
+    for id in self.ids() {
+      if !self.get(id).is_some_and(&mut keep) {
+        self.remove(id);
+      }
+    }
+  }
+}
+
+impl<T> FromIterator<T> for Arena<T> {
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut arena = Arena::new();
+    for value in iter {
+      arena.insert(value);
+    }
+
+    arena
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn stale_ids_miss_the_reused_slot() {
+    let mut arena = Arena::new();
+    let old = arena.insert("old");
+    arena.remove(old);
+    let new = arena.insert("new");
+
+    assert_ne!(old, new);
+    assert_eq!(arena.get(old), None);
+    assert_eq!(arena.remove(old), None);
+    assert_eq!(arena.get(new), Some(&"new"));
+  }
+
+  #[test]
+  fn despawning_twice_only_counts_once() {
+    let mut arena = Arena::new();
+    let id = arena.insert(1);
+
+    assert!(arena.despawn(id));
+    assert!(!arena.despawn(id));
+    assert!(!arena.is_alive(id));
+    assert_eq!(arena.get(id), Some(&1));
+
+    arena.flush();
+    assert_eq!(arena.get(id), None);
+    assert!(arena.is_empty());
+    assert!(!arena.despawn(id));
+  }
+}
@@ -1,3 +1,10 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use image::ImageError;
+use kira::sound::FromFileError;
+use opengl_graphics::{GlyphCache, TextureSettings};
+
 pub const PLAYER_TEXTURE_NAME: &str = "ship.png";
 pub const BULLET_TEXTURE_NAME: &str = "bullet.png";
 pub const BACKGROUND_TEXTURE_NAME: &str = "space.jpg";
@@ -5,8 +12,114 @@ pub const BACKGROUND_TEXTURE_NAME: &str = "space.jpg";
 pub const CRAB_TEXTURE_NAME: &str = "crab.png";
 pub const SQUID_TEXTURE_NAME: &str = "squid.png";
 pub const OCTOPUS_TEXTURE_NAME: &str = "octopus.png";
+pub const SAUCER_TEXTURE_NAME: &str = "saucer.png";
 
 pub const PLAYER_EXPLOSION_SOUND_NAME: &str = "player_explosion.wav";
 pub const ENEMY_EXPLOSION_SOUND_NAME: &str = "enemy_explosion.wav";
 pub const PLAYER_HIT_SOUND_NAME: &str = "hit.wav";
 pub const PLAYER_LASER_SHOOT_SOUND_NAME: &str = "laser_shoot.wav";
+pub const SAUCER_SOUND_NAME: &str = "saucer.wav";
+pub const MENU_SELECT_SOUND_NAME: &str = "menu_select.wav";
+
+pub const TITLE_MUSIC_NAME: &str = "title.wav";
+pub const GAME_OVER_MUSIC_NAME: &str = "game_over.wav";
+pub const MARCH_NOTE_NAMES: [&str; 4] =
+  ["march_1.wav", "march_2.wav", "march_3.wav", "march_4.wav"];
+
+const BUILTIN_FONT: &[u8] = include_bytes!("../../assets/fonts/Roboto-Regular.ttf");
+
+/// Anything that went wrong loading a file from the assets folder or talking to the audio device.
+#[derive(Debug)]
+pub enum AssetError {
+  /// The file is missing or couldn't be read.
+  Io { path: PathBuf, message: String },
+  /// The file was read but isn't a usable image, sound or font.
+  Decode { path: PathBuf, message: String },
+  /// No audio device, or the audio thread turned the sound down.
+  Audio(String),
+}
+
+impl AssetError {
+  pub fn image(path: &Path, err: ImageError) -> Self {
+    match err {
+      ImageError::IoError(err) => AssetError::Io {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+      },
+      err => AssetError::Decode {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+      },
+    }
+  }
+
+  pub fn sound(path: &Path, err: FromFileError) -> Self {
+    match err {
+      FromFileError::IoError(err) => AssetError::Io {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+      },
+      err => AssetError::Decode {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+      },
+    }
+  }
+}
+
+impl fmt::Display for AssetError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      AssetError::Io { path, message } => {
+        write!(f, "couldn't read {}: {}", path.display(), message)
+      }
+      AssetError::Decode { path, message } => {
+        write!(f, "couldn't decode {}: {}", path.display(), message)
+      }
+      AssetError::Audio(message) => write!(f, "audio: {}", message),
+    }
+  }
+}
+
+/// The game font from the assets folder, or the copy built into the binary if it can't be read.
+pub fn load_font(path: &Path) -> GlyphCache<'static> {
+  match GlyphCache::new(path, (), TextureSettings::new()) {
+    Ok(glyphs) => glyphs,
+    Err(err) => {
+      let err = AssetError::Io {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+      };
+      log::warn!("{}, using the built-in font", err);
+
+      GlyphCache::from_bytes(BUILTIN_FONT, (), TextureSettings::new())
+        .expect("the built-in font is valid")
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn open(path: &Path) -> AssetError {
+    image::open(path)
+      .map(|_| ())
+      .map_err(|err| AssetError::image(path, err))
+      .unwrap_err()
+  }
+
+  #[test]
+  fn missing_and_broken_images_are_told_apart() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    assert!(matches!(
+      open(&root.join("assets/images/missing.png")),
+      AssetError::Io { .. }
+    ));
+    assert!(matches!(
+      open(&root.join("assets/fonts/Roboto-Regular.ttf")),
+      AssetError::Decode { .. }
+    ));
+  }
+}
@@ -0,0 +1,143 @@
+use std::path::Path;
+
+use super::music::Music;
+use super::settings::AudioSettings;
+use super::sounds::{SoundEffect, SoundsManager};
+
+/// Everything the game asks of the audio, so it can run on kira or on nothing at all.
+pub trait AudioBackend {
+  /// Plays the effect once, panned towards `x` in window coordinates or centred without one.
+  /// `now` is the game time from [`Clock::now`](super::clock::Clock::now).
+  fn play(&mut self, sound_effect: SoundEffect, x: Option<f64>, now: f64);
+  /// Keeps playing the effect over and over until [`AudioBackend::stop_loop`] is called.
+  fn play_loop(&mut self, sound_effect: SoundEffect, x: Option<f64>);
+  fn stop_loop(&mut self, sound_effect: SoundEffect);
+  /// Follows a moving emitter with a looping effect, if it is playing.
+  fn pan_loop(&mut self, sound_effect: SoundEffect, x: f64);
+  /// Crossfades to `music`, nothing happens if it is already playing.
+  fn play_music(&mut self, music: Music);
+  /// Advances the march by `dt` game seconds, faster the fewer invaders are left.
+  fn update_music(&mut self, dt: f64, enemies_left: usize, enemies_total: usize);
+  fn pause_music(&mut self, paused: bool);
+  fn settings(&self) -> &AudioSettings;
+  /// Silences or restores every bus at once.
+  fn toggle_mute(&mut self);
+}
+
+/// Kira on the default output device, or [`NullAudio`] when `enabled` is false or there is no
+/// device to play on.
+pub fn open(assets: &Path, settings: &AudioSettings, enabled: bool) -> Box<dyn AudioBackend> {
+  if !enabled {
+    return Box::new(NullAudio::new(settings));
+  }
+
+  match SoundsManager::new(assets, settings) {
+    Ok(sounds) => Box::new(sounds),
+    Err(err) => {
+      log::warn!("{}, the game will be silent", err);
+      Box::new(NullAudio::new(settings))
+    }
+  }
+}
+
+/// Plays nothing, for running the game without a sound device.
+pub struct NullAudio {
+  settings: AudioSettings,
+}
+
+impl NullAudio {
+  pub fn new(settings: &AudioSettings) -> Self {
+    Self {
+      settings: settings.clone(),
+    }
+  }
+}
+
+impl AudioBackend for NullAudio {
+  fn play(&mut self, _: SoundEffect, _: Option<f64>, _: f64) {}
+
+  fn play_loop(&mut self, _: SoundEffect, _: Option<f64>) {}
+
+  fn stop_loop(&mut self, _: SoundEffect) {}
+
+  fn pan_loop(&mut self, _: SoundEffect, _: f64) {}
+
+  fn play_music(&mut self, _: Music) {}
+
+  fn update_music(&mut self, _: f64, _: usize, _: usize) {}
+
+  fn pause_music(&mut self, _: bool) {}
+
+  fn settings(&self) -> &AudioSettings {
+    &self.settings
+  }
+
+  fn toggle_mute(&mut self) {
+    self.settings.muted = !self.settings.muted;
+  }
+}
+
+/// One effect the game asked for, as [`RecordingAudio`] saw it.
+#[cfg(test)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayedEffect {
+  pub sound_effect: SoundEffect,
+  pub x: Option<f64>,
+  pub looping: bool,
+}
+
+/// [`NullAudio`] that remembers which effects it was asked for, in order and where, so the sounds
+/// can be checked without listening to them. How loud they would have been is up to the config
+/// and isn't recorded.
+#[cfg(test)]
+pub struct RecordingAudio {
+  audio: NullAudio,
+  pub played: Vec<PlayedEffect>,
+}
+
+#[cfg(test)]
+impl RecordingAudio {
+  pub fn new() -> Self {
+    Self {
+      audio: NullAudio::new(&AudioSettings::default()),
+      played: Vec::new(),
+    }
+  }
+
+  fn record(&mut self, sound_effect: SoundEffect, x: Option<f64>, looping: bool) {
+    self.played.push(PlayedEffect {
+      sound_effect,
+      x,
+      looping,
+    });
+  }
+}
+
+#[cfg(test)]
+impl AudioBackend for RecordingAudio {
+  fn play(&mut self, sound_effect: SoundEffect, x: Option<f64>, _: f64) {
+    self.record(sound_effect, x, false);
+  }
+
+  fn play_loop(&mut self, sound_effect: SoundEffect, x: Option<f64>) {
+    self.record(sound_effect, x, true);
+  }
+
+  fn stop_loop(&mut self, _: SoundEffect) {}
+
+  fn pan_loop(&mut self, _: SoundEffect, _: f64) {}
+
+  fn play_music(&mut self, _: Music) {}
+
+  fn update_music(&mut self, _: f64, _: usize, _: usize) {}
+
+  fn pause_music(&mut self, _: bool) {}
+
+  fn settings(&self) -> &AudioSettings {
+    self.audio.settings()
+  }
+
+  fn toggle_mute(&mut self) {
+    self.audio.toggle_mute()
+  }
+}
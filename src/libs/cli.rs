@@ -0,0 +1,39 @@
+use std::env;
+use std::path::PathBuf;
+
+#[derive(Debug, Default)]
+pub struct Args {
+  pub seed: Option<u64>,
+  pub config: Option<PathBuf>,
+  pub record: Option<PathBuf>,
+  pub replay: Option<PathBuf>,
+  /// Runs without opening the sound device, for machines that have none.
+  pub no_audio: bool,
+}
+
+impl Args {
+  pub fn parse() -> Self {
+    let mut args = Self::default();
+    let mut iter = env::args().skip(1);
+
+    while let Some(arg) = iter.next() {
+      match arg.as_str() {
+        "--seed" => {
+          let value = iter.next().expect("--seed expects a value");
+          args.seed = Some(
+            value
+              .parse()
+              .expect("--seed expects an unsigned 64-bit integer"),
+          );
+        }
+        "--config" => args.config = Some(iter.next().expect("--config expects a path").into()),
+        "--record" => args.record = Some(iter.next().expect("--record expects a path").into()),
+        "--replay" => args.replay = Some(iter.next().expect("--replay expects a path").into()),
+        "--no-audio" => args.no_audio = true,
+        _ => eprintln!("ignoring unknown argument: {}", arg),
+      }
+    }
+
+    args
+  }
+}
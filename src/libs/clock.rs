@@ -0,0 +1,139 @@
+/// Most simulation steps run for a single frame, anything beyond is dropped so a long stall
+/// (dragging the window, a breakpoint) doesn't make the game fast-forward to catch up.
+const MAX_STEPS_PER_FRAME: u32 = 5;
+
+/// The simulation's own time, advanced in fixed steps from the real frame times it is fed.
+///
+/// Entities never look at the wall clock, so pausing, slowing down or replaying the game only
+/// has to happen here.
+#[derive(Debug, Clone)]
+pub struct Clock {
+  step: f64,
+  scale: f64,
+  paused: bool,
+  accumulator: f64,
+  steps: u32,
+  time: f64,
+}
+
+impl Clock {
+  pub fn new(tick_rate: u32, scale: f64) -> Self {
+    Self {
+      step: 1.0 / tick_rate as f64,
+      scale,
+      paused: false,
+      accumulator: 0.0,
+      steps: 0,
+      time: 0.0,
+    }
+  }
+
+  /// Banks `real_dt` seconds of real time, scaled, to be spent by [`Clock::next_step`].
+  pub fn accumulate(&mut self, real_dt: f64) {
+    self.accumulator += real_dt * self.scale;
+    self.steps = 0;
+  }
+
+  /// Takes one fixed step out of the banked time, returns false once there isn't enough left.
+  pub fn next_step(&mut self) -> bool {
+    if self.accumulator < self.step {
+      return false;
+    }
+
+    if self.steps == MAX_STEPS_PER_FRAME {
+      self.accumulator %= self.step;
+      return false;
+    }
+
+    self.accumulator -= self.step;
+    self.steps += 1;
+    if !self.paused {
+      self.time += self.step;
+    }
+
+    true
+  }
+
+  /// The simulated seconds covered by a step, nothing moves while paused.
+  pub fn dt(&self) -> f64 {
+    if self.paused {
+      0.0
+    } else {
+      self.step
+    }
+  }
+
+  /// How far the banked time has got towards the next step, from 0 to 1, for drawing in between.
+  pub fn alpha(&self) -> f64 {
+    (self.accumulator / self.step).min(1.0)
+  }
+
+  /// Simulated seconds since the clock started.
+  pub fn now(&self) -> f64 {
+    self.time
+  }
+
+  pub fn pause(&mut self) {
+    self.paused = true;
+  }
+
+  pub fn resume(&mut self) {
+    self.paused = false;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn steps(clock: &mut Clock, real_dt: f64) -> u32 {
+    clock.accumulate(real_dt);
+    let mut steps = 0;
+    while clock.next_step() {
+      steps += 1;
+    }
+
+    steps
+  }
+
+  #[test]
+  fn leftover_time_carries_over_to_the_next_frame() {
+    let mut clock = Clock::new(60, 1.0);
+
+    assert_eq!(steps(&mut clock, 0.01), 0);
+    assert_eq!(steps(&mut clock, 0.01), 1);
+    assert_eq!(steps(&mut clock, 0.03), 2);
+  }
+
+  #[test]
+  fn a_stall_runs_a_few_steps_and_drops_the_rest() {
+    let mut clock = Clock::new(60, 1.0);
+
+    assert_eq!(steps(&mut clock, 2.0), MAX_STEPS_PER_FRAME);
+    assert_eq!(steps(&mut clock, 0.0), 0);
+  }
+
+  #[test]
+  fn the_time_scale_changes_how_many_steps_a_frame_runs() {
+    let mut slow = Clock::new(60, 0.5);
+    let mut fast = Clock::new(60, 2.0);
+
+    assert_eq!(steps(&mut slow, 0.11), 3);
+    assert_eq!(steps(&mut fast, 0.04), 4);
+  }
+
+  #[test]
+  fn paused_steps_cover_no_time() {
+    let mut clock = Clock::new(60, 1.0);
+
+    clock.pause();
+    assert_eq!(clock.dt(), 0.0);
+    steps(&mut clock, 0.05);
+    assert_eq!(clock.now(), 0.0);
+
+    clock.resume();
+    assert_eq!(clock.dt(), 1.0 / 60.0);
+    steps(&mut clock, 0.02);
+    assert_eq!(clock.now(), 1.0 / 60.0);
+  }
+}
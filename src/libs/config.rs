@@ -1,15 +1,19 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
 use cgmath::Vector2;
+use serde::{Deserialize, Serialize};
+
+use super::input::Bindings;
+use super::masks::CollisionShape;
 
+// defaults, overridden by the config file loaded at startup
 pub const WINDOW_WIDTH: f64 = 650.0;
 pub const WINDOW_HEIGHT: f64 = 450.0;
 pub const WINDOW_NAME: &str = "RustyInvaders";
-pub const WINDOW_CENTER: Vector2<f64> = Vector2 {
-  x: WINDOW_WIDTH / 2.0,
-  y: WINDOW_HEIGHT / 2.0,
-};
 
-pub const PLAYER_INIT_X: f64 = WINDOW_CENTER.x;
-pub const PLAYER_INIT_Y: f64 = WINDOW_HEIGHT - 50.0;
 pub const PLAYER_WIDTH: f64 = 30.0;
 pub const PLAYER_HEIGHT: f64 = 26.0;
 
@@ -27,24 +31,678 @@ pub const ENEMY_MAX_DAMAGE: f64 = 12.0;
 pub const ENEMY_MIN_DAMAGE: f64 = 2.0;
 pub const ENEMY_GRID_COLS: u8 = 10;
 pub const ENEMY_GRID_ROWS: u8 = 5;
-pub const ENEMY_GRID_WIDTH: f64 = ENEMY_GRID_COLS as f64 * (ENEMY_WIDTH + ENEMY_SPACING);
-pub const ENEMY_GRID_X: f64 = (WINDOW_WIDTH - (ENEMY_GRID_WIDTH)) / 2.0;
 pub const ENEMY_GRID_Y: f64 = 10.0;
 
 pub const ENEMY_OCTOPUS_POINTS: u64 = 10;
 pub const ENEMY_CRAB_POINTS: u64 = 20;
 pub const ENEMY_SQUID_POINTS: u64 = 40;
 
+pub const BUNKER_COUNT: u8 = 4;
+pub const BUNKER_COLS: u8 = 22;
+pub const BUNKER_ROWS: u8 = 16;
+pub const BUNKER_CELL_SIZE: f64 = 3.0;
+pub const BUNKER_GAP: f64 = 30.0;
+
+pub const SAUCER_WIDTH: f64 = 36.0;
+pub const SAUCER_HEIGHT: f64 = 14.0;
+pub const SAUCER_SPEED: f64 = 60.0;
+pub const SAUCER_Y: f64 = 12.0;
+pub const SAUCER_MIN_INTERVAL: f64 = 15.0;
+pub const SAUCER_MAX_INTERVAL: f64 = 30.0;
+pub const SAUCER_POINTS: [u64; 4] = [50, 100, 150, 300];
+
+pub const PLAYER_LIVES: u32 = 3;
+pub const PLAYER_DEATH_DURATION: f64 = 1.0;
+pub const PLAYER_INVULNERABILITY: f64 = 2.0;
+pub const PLAYER_BONUS_LIFE_SCORE: u64 = 1500;
+
 pub const BULLET_WIDTH: f64 = 5.0;
 pub const BULLET_HEIGHT: f64 = BULLET_WIDTH * 2.0;
 
 pub const PLAYER_VELOCITY: Vector2<f64> = Vector2 { x: 80.0, y: 0.0 };
-pub const PLAYER_BULLET_ACCELERATION: Vector2<f64> = Vector2 { x: 0.0, y: -9.8 };
+pub const PLAYER_BULLET_ACCELERATION: Vector2<f64> = Vector2 { x: 0.0, y: -3000.0 };
 pub const PLAYER_BULLET_VELOCITY: Vector2<f64> = Vector2 { x: 0.0, y: -50.0 };
 
-pub const ENEMY_BULLET_ACCELERATION: Vector2<f64> = Vector2 { x: 0.0, y: 0.0 };
+pub const ENEMY_BULLET_ACCELERATION: Vector2<f64> = Vector2 { x: 0.0, y: 600.0 };
 pub const ENEMY_BULLET_VELOCITY: Vector2<f64> = Vector2 { x: 0.0, y: 10.0 };
 
 pub const PLAYER_BULLETS_FREQUENCY: u32 = 5;
 
+pub const TICK_RATE: u32 = 60;
+pub const TIME_SCALE: f64 = 1.0;
+
+pub const MUSIC_VOLUME: f64 = 0.4;
+pub const PLAYER_EXPLOSION_VOLUME: f64 = 1.0;
+pub const PLAYER_HIT_VOLUME: f64 = 1.0;
+pub const PLAYER_LASER_SHOOT_VOLUME: f64 = 1.0;
+pub const ENEMY_EXPLOSION_VOLUME: f64 = 0.2;
+pub const SAUCER_VOLUME: f64 = 0.3;
+pub const SAUCER_EXPLOSION_VOLUME: f64 = 0.5;
+pub const MENU_SELECT_VOLUME: f64 = 0.5;
+pub const PLAYER_EXPLOSION_MAX_VOICES: usize = 1;
+pub const PLAYER_HIT_MAX_VOICES: usize = 2;
+pub const PLAYER_LASER_SHOOT_MAX_VOICES: usize = 3;
+pub const ENEMY_EXPLOSION_MAX_VOICES: usize = 4;
+pub const SAUCER_MAX_VOICES: usize = 1;
+pub const SAUCER_EXPLOSION_MAX_VOICES: usize = 1;
+pub const MENU_SELECT_MAX_VOICES: usize = 1;
+pub const SOUND_PITCH_VARIATION: f64 = 0.05;
+
 pub const FONT_NAME: &str = "Roboto-Regular.ttf";
+pub const CONFIG_FILE_NAME: &str = "config.ron";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// The active configuration, falling back to the defaults when none was installed.
+pub fn config() -> &'static Config {
+  CONFIG.get_or_init(Config::default)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+  pub window: WindowConfig,
+  pub simulation: SimulationConfig,
+  pub player: PlayerConfig,
+  pub lives: LivesConfig,
+  pub health: HealthConfig,
+  pub enemy: EnemyConfig,
+  pub bullet: BulletConfig,
+  pub bunker: BunkerConfig,
+  pub saucer: SaucerConfig,
+  pub collision: CollisionConfig,
+  pub music: MusicConfig,
+  pub sounds: SoundsConfig,
+  pub bindings: Bindings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+  pub width: f64,
+  pub height: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerConfig {
+  pub width: f64,
+  pub height: f64,
+  pub velocity: Vector2<f64>,
+  pub bullet_acceleration: Vector2<f64>,
+  pub bullet_velocity: Vector2<f64>,
+  pub bullets_frequency: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LivesConfig {
+  pub count: u32,
+  /// Seconds the death animation lasts before the ship respawns.
+  pub death_duration: f64,
+  /// Seconds after a respawn during which enemy bullets pass through the ship.
+  pub invulnerability: f64,
+  /// Score that awards one extra life, 0 turns it off.
+  pub bonus_score: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+  pub bar_width: f64,
+  pub bar_height: f64,
+  pub bar_stroke: f64,
+  pub regen_min: f64,
+  pub regen_max: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnemyConfig {
+  pub width: f64,
+  pub height: f64,
+  pub speed: f64,
+  pub spacing: f64,
+  pub min_damage: f64,
+  pub max_damage: f64,
+  pub grid_cols: u8,
+  pub grid_rows: u8,
+  pub grid_y: f64,
+  pub bullet_acceleration: Vector2<f64>,
+  pub bullet_velocity: Vector2<f64>,
+  pub octopus_points: u64,
+  pub crab_points: u64,
+  pub squid_points: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BulletConfig {
+  pub width: f64,
+  pub height: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BunkerConfig {
+  pub count: u8,
+  pub cols: u8,
+  pub rows: u8,
+  pub cell_size: f64,
+  /// Space left between the bottom of the bunkers and the player.
+  pub gap: f64,
+  /// Keep the damaged bunkers between rounds instead of rebuilding them.
+  pub persist: bool,
+}
+
+/// `Rect` or `Mask` per entity type, masks ignore the transparent parts of the sprite.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CollisionConfig {
+  pub player: CollisionShape,
+  pub bullet: CollisionShape,
+  pub saucer: CollisionShape,
+  pub enemies: CollisionShape,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundsConfig {
+  pub player_explosion: EffectConfig,
+  pub player_hit: EffectConfig,
+  pub player_laser_shoot: EffectConfig,
+  pub enemy_explosion: EffectConfig,
+  pub saucer: EffectConfig,
+  /// The enemy explosion again, louder when it is the saucer going down.
+  pub saucer_explosion: EffectConfig,
+  pub menu_select: EffectConfig,
+  /// Sound effects play up to this much faster or slower at random, 0 turns it off.
+  pub pitch_variation: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectConfig {
+  /// Volume before the bus and master volumes, from 0 to 1.
+  pub volume: f64,
+  /// Copies of the effect that can play at once, the oldest one makes room for a new one.
+  pub max_voices: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MusicConfig {
+  /// Volume of the tracks and the march, from 0 to 1.
+  pub volume: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+  /// Simulation steps per second, independent of the frame rate.
+  pub tick_rate: u32,
+  /// How fast game time runs compared to real time.
+  pub time_scale: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaucerConfig {
+  pub width: f64,
+  pub height: f64,
+  pub speed: f64,
+  pub y: f64,
+  /// Seconds between two saucers, picked at random in this range.
+  pub min_interval: f64,
+  pub max_interval: f64,
+  /// Bonus scores a hit can award, one is picked at random for every saucer.
+  pub points: Vec<u64>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+  Io(String),
+  Parse { key: String, message: String },
+  Invalid { key: &'static str, message: String },
+}
+
+impl fmt::Display for ConfigError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ConfigError::Io(message) => write!(f, "{}", message),
+      ConfigError::Parse { key, message } => write!(f, "`{}`: {}", key, message),
+      ConfigError::Invalid { key, message } => write!(f, "`{}` {}", key, message),
+    }
+  }
+}
+
+impl Default for WindowConfig {
+  fn default() -> Self {
+    Self {
+      width: WINDOW_WIDTH,
+      height: WINDOW_HEIGHT,
+    }
+  }
+}
+
+impl Default for SimulationConfig {
+  fn default() -> Self {
+    Self {
+      tick_rate: TICK_RATE,
+      time_scale: TIME_SCALE,
+    }
+  }
+}
+
+impl Default for PlayerConfig {
+  fn default() -> Self {
+    Self {
+      width: PLAYER_WIDTH,
+      height: PLAYER_HEIGHT,
+      velocity: PLAYER_VELOCITY,
+      bullet_acceleration: PLAYER_BULLET_ACCELERATION,
+      bullet_velocity: PLAYER_BULLET_VELOCITY,
+      bullets_frequency: PLAYER_BULLETS_FREQUENCY,
+    }
+  }
+}
+
+impl Default for LivesConfig {
+  fn default() -> Self {
+    Self {
+      count: PLAYER_LIVES,
+      death_duration: PLAYER_DEATH_DURATION,
+      invulnerability: PLAYER_INVULNERABILITY,
+      bonus_score: PLAYER_BONUS_LIFE_SCORE,
+    }
+  }
+}
+
+impl Default for HealthConfig {
+  fn default() -> Self {
+    Self {
+      bar_width: HEALTH_BAR_WIDTH,
+      bar_height: HEALTH_BAR_HEIGHT,
+      bar_stroke: HEALTH_BAR_STROKE,
+      regen_min: HEALTH_REGEN_MIN_VALUE,
+      regen_max: HEALTH_REGEN_MAX_VALUE,
+    }
+  }
+}
+
+impl Default for EnemyConfig {
+  fn default() -> Self {
+    Self {
+      width: ENEMY_WIDTH,
+      height: ENEMY_HEIGHT,
+      speed: ENEMY_SPEED,
+      spacing: ENEMY_SPACING,
+      min_damage: ENEMY_MIN_DAMAGE,
+      max_damage: ENEMY_MAX_DAMAGE,
+      grid_cols: ENEMY_GRID_COLS,
+      grid_rows: ENEMY_GRID_ROWS,
+      grid_y: ENEMY_GRID_Y,
+      bullet_acceleration: ENEMY_BULLET_ACCELERATION,
+      bullet_velocity: ENEMY_BULLET_VELOCITY,
+      octopus_points: ENEMY_OCTOPUS_POINTS,
+      crab_points: ENEMY_CRAB_POINTS,
+      squid_points: ENEMY_SQUID_POINTS,
+    }
+  }
+}
+
+impl Default for BulletConfig {
+  fn default() -> Self {
+    Self {
+      width: BULLET_WIDTH,
+      height: BULLET_HEIGHT,
+    }
+  }
+}
+
+impl Default for BunkerConfig {
+  fn default() -> Self {
+    Self {
+      count: BUNKER_COUNT,
+      cols: BUNKER_COLS,
+      rows: BUNKER_ROWS,
+      cell_size: BUNKER_CELL_SIZE,
+      gap: BUNKER_GAP,
+      persist: false,
+    }
+  }
+}
+
+impl Default for CollisionConfig {
+  fn default() -> Self {
+    Self {
+      player: CollisionShape::Mask,
+      bullet: CollisionShape::Rect,
+      saucer: CollisionShape::Mask,
+      enemies: CollisionShape::Mask,
+    }
+  }
+}
+
+impl Default for SoundsConfig {
+  fn default() -> Self {
+    Self {
+      player_explosion: EffectConfig::new(PLAYER_EXPLOSION_VOLUME, PLAYER_EXPLOSION_MAX_VOICES),
+      player_hit: EffectConfig::new(PLAYER_HIT_VOLUME, PLAYER_HIT_MAX_VOICES),
+      player_laser_shoot: EffectConfig::new(
+        PLAYER_LASER_SHOOT_VOLUME,
+        PLAYER_LASER_SHOOT_MAX_VOICES,
+      ),
+      enemy_explosion: EffectConfig::new(ENEMY_EXPLOSION_VOLUME, ENEMY_EXPLOSION_MAX_VOICES),
+      saucer: EffectConfig::new(SAUCER_VOLUME, SAUCER_MAX_VOICES),
+      saucer_explosion: EffectConfig::new(SAUCER_EXPLOSION_VOLUME, SAUCER_EXPLOSION_MAX_VOICES),
+      menu_select: EffectConfig::new(MENU_SELECT_VOLUME, MENU_SELECT_MAX_VOICES),
+      pitch_variation: SOUND_PITCH_VARIATION,
+    }
+  }
+}
+
+impl EffectConfig {
+  const fn new(volume: f64, max_voices: usize) -> Self {
+    Self { volume, max_voices }
+  }
+}
+
+impl Default for MusicConfig {
+  fn default() -> Self {
+    Self {
+      volume: MUSIC_VOLUME,
+    }
+  }
+}
+
+impl Default for SaucerConfig {
+  fn default() -> Self {
+    Self {
+      width: SAUCER_WIDTH,
+      height: SAUCER_HEIGHT,
+      speed: SAUCER_SPEED,
+      y: SAUCER_Y,
+      min_interval: SAUCER_MIN_INTERVAL,
+      max_interval: SAUCER_MAX_INTERVAL,
+      points: SAUCER_POINTS.to_vec(),
+    }
+  }
+}
+
+impl Config {
+  /// Reads a RON config file on top of the defaults. Keys the game doesn't know about are
+  /// returned alongside so they can be reported without failing the load.
+  pub fn load(path: &Path) -> Result<(Self, Vec<String>), ConfigError> {
+    let contents = fs::read_to_string(path)
+      .map_err(|err| ConfigError::Io(format!("{}: {}", path.display(), err)))?;
+
+    let mut deserializer =
+      ron::Deserializer::from_str(&contents).map_err(|err| ConfigError::Parse {
+        key: String::from("."),
+        message: err.to_string(),
+      })?;
+
+    let mut unknown_keys = Vec::new();
+    let mut on_unknown = |key: serde_ignored::Path| unknown_keys.push(key.to_string());
+    let result: Result<Config, _> = serde_path_to_error::deserialize(
+      serde_ignored::Deserializer::new(&mut deserializer, &mut on_unknown),
+    );
+
+    let config = result.map_err(|err| ConfigError::Parse {
+      key: err.path().to_string(),
+      message: deserializer.span_error(err.into_inner()).to_string(),
+    })?;
+
+    config.validate()?;
+
+    Ok((config, unknown_keys))
+  }
+
+  /// Makes this the configuration returned by [`config`] for the rest of the run.
+  pub fn install(self) {
+    CONFIG
+      .set(self)
+      .expect("config was read before being installed");
+  }
+
+  /// A hash of every setting, so files recorded under one config can tell when they are read under
+  /// another.
+  pub fn fingerprint(&self) -> u64 {
+    // FNV-1a, which unlike `DefaultHasher` is stable across builds
+    let serialized = ron::to_string(self).expect("config always serializes");
+    serialized
+      .bytes()
+      .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+      })
+  }
+
+  pub fn validate(&self) -> Result<(), ConfigError> {
+    fn check(valid: bool, key: &'static str, message: &str) -> Result<(), ConfigError> {
+      if valid {
+        Ok(())
+      } else {
+        Err(ConfigError::Invalid {
+          key,
+          message: String::from(message),
+        })
+      }
+    }
+
+    check(self.window.width > 0.0, "window.width", "must be positive")?;
+    check(
+      self.window.height > 0.0,
+      "window.height",
+      "must be positive",
+    )?;
+
+    check(
+      self.simulation.tick_rate > 0,
+      "simulation.tick_rate",
+      "must be at least 1",
+    )?;
+    check(
+      self.simulation.time_scale > 0.0,
+      "simulation.time_scale",
+      "must be positive",
+    )?;
+
+    check(self.player.width > 0.0, "player.width", "must be positive")?;
+    check(
+      self.player.height > 0.0,
+      "player.height",
+      "must be positive",
+    )?;
+    check(
+      self.player.bullets_frequency > 0,
+      "player.bullets_frequency",
+      "must be at least 1",
+    )?;
+
+    check(self.lives.count > 0, "lives.count", "must be at least 1")?;
+    check(
+      self.lives.death_duration > 0.0,
+      "lives.death_duration",
+      "must be positive",
+    )?;
+    check(
+      self.lives.invulnerability >= 0.0,
+      "lives.invulnerability",
+      "must not be negative",
+    )?;
+
+    check(
+      self.health.regen_min < self.health.regen_max,
+      "health.regen_min",
+      "must be lower than `health.regen_max`",
+    )?;
+
+    check(self.enemy.width > 0.0, "enemy.width", "must be positive")?;
+    check(self.enemy.height > 0.0, "enemy.height", "must be positive")?;
+    check(
+      self.enemy.speed >= 0.0,
+      "enemy.speed",
+      "must not be negative",
+    )?;
+    check(
+      self.enemy.min_damage < self.enemy.max_damage,
+      "enemy.min_damage",
+      "must be lower than `enemy.max_damage`",
+    )?;
+    check(
+      self.enemy.grid_cols > 0,
+      "enemy.grid_cols",
+      "must be at least 1",
+    )?;
+    check(
+      self.enemy.grid_rows > 0,
+      "enemy.grid_rows",
+      "must be at least 1",
+    )?;
+    check(
+      self.enemy_grid_width() <= self.window.width - 100.0,
+      "enemy.grid_cols",
+      "makes the grid wider than the window",
+    )?;
+
+    check(self.bullet.width > 0.0, "bullet.width", "must be positive")?;
+    check(
+      self.bullet.height > 0.0,
+      "bullet.height",
+      "must be positive",
+    )?;
+
+    check(self.bunker.cols > 0, "bunker.cols", "must be at least 1")?;
+    check(self.bunker.rows > 0, "bunker.rows", "must be at least 1")?;
+    check(
+      self.bunker.cell_size > 0.0,
+      "bunker.cell_size",
+      "must be positive",
+    )?;
+    check(self.bunker.gap >= 0.0, "bunker.gap", "must not be negative")?;
+    check(
+      self.bunker.count as f64 * self.bunker_width() <= self.window.width,
+      "bunker.count",
+      "makes the bunkers wider than the window",
+    )?;
+
+    check(self.saucer.speed > 0.0, "saucer.speed", "must be positive")?;
+    check(
+      self.saucer.min_interval >= 0.0,
+      "saucer.min_interval",
+      "must not be negative",
+    )?;
+    check(
+      self.saucer.min_interval <= self.saucer.max_interval,
+      "saucer.min_interval",
+      "must not be greater than `saucer.max_interval`",
+    )?;
+    check(
+      !self.saucer.points.is_empty(),
+      "saucer.points",
+      "must not be empty",
+    )?;
+
+    let sounds = &self.sounds;
+    for (effect, key) in [
+      (&sounds.player_explosion, "sounds.player_explosion"),
+      (&sounds.player_hit, "sounds.player_hit"),
+      (&sounds.player_laser_shoot, "sounds.player_laser_shoot"),
+      (&sounds.enemy_explosion, "sounds.enemy_explosion"),
+      (&sounds.saucer, "sounds.saucer"),
+      (&sounds.saucer_explosion, "sounds.saucer_explosion"),
+      (&sounds.menu_select, "sounds.menu_select"),
+    ] {
+      check(
+        (0.0..=1.0).contains(&effect.volume),
+        key,
+        "volume must be between 0 and 1",
+      )?;
+      check(effect.max_voices > 0, key, "max_voices must be at least 1")?;
+    }
+    check(
+      (0.0..1.0).contains(&sounds.pitch_variation),
+      "sounds.pitch_variation",
+      "must be between 0 and 1",
+    )?;
+
+    check(
+      (0.0..=1.0).contains(&self.music.volume),
+      "music.volume",
+      "must be between 0 and 1",
+    )?;
+
+    check(
+      (0.0..1.0).contains(&self.bindings.deadzone),
+      "bindings.deadzone",
+      "must be between 0 and 1",
+    )?;
+
+    Ok(())
+  }
+
+  pub fn window_center(&self) -> Vector2<f64> {
+    Vector2 {
+      x: self.window.width / 2.0,
+      y: self.window.height / 2.0,
+    }
+  }
+
+  pub fn player_init_x(&self) -> f64 {
+    self.window_center().x
+  }
+
+  pub fn player_init_y(&self) -> f64 {
+    self.window.height - 50.0
+  }
+
+  pub fn enemy_grid_width(&self) -> f64 {
+    self.enemy.grid_cols as f64 * (self.enemy.width + self.enemy.spacing)
+  }
+
+  pub fn bunker_width(&self) -> f64 {
+    self.bunker.cols as f64 * self.bunker.cell_size
+  }
+
+  pub fn enemy_grid_x(&self) -> f64 {
+    (self.window.width - self.enemy_grid_width()) / 2.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn defaults_are_valid() {
+    assert!(Config::default().validate().is_ok());
+  }
+
+  #[test]
+  fn bunkers_must_fit_side_by_side() {
+    let mut config = Config::default();
+    config.bunker.count = 40;
+
+    assert!(matches!(
+      config.validate(),
+      Err(ConfigError::Invalid {
+        key: "bunker.count",
+        ..
+      })
+    ));
+  }
+
+  #[test]
+  fn bunker_gap_must_not_be_negative() {
+    let mut config = Config::default();
+    config.bunker.gap = -10.0;
+
+    assert!(matches!(
+      config.validate(),
+      Err(ConfigError::Invalid {
+        key: "bunker.gap",
+        ..
+      })
+    ));
+  }
+}
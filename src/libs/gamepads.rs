@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use gilrs::{Axis, EventType, GamepadId, Gilrs};
+use piston::{
+  Button, ButtonArgs, ButtonState, ControllerAxisArgs, ControllerButton, ControllerHat, HatState,
+};
+
+/// Something a gamepad did, in the shape of piston's own controller events.
+pub enum GamepadEvent {
+  Button(ButtonArgs),
+  Axis(ControllerAxisArgs),
+  Disconnected(u32),
+}
+
+/// Reads connected gamepads, which the window backend doesn't report.
+pub struct Gamepads {
+  gilrs: Option<Gilrs>,
+  /// D-pad buttons held on each pad, reported together as a single hat.
+  dpads: HashMap<GamepadId, [bool; 4]>,
+}
+
+impl Gamepads {
+  pub fn new() -> Self {
+    let gilrs = match Gilrs::new() {
+      Ok(gilrs) => Some(gilrs),
+      Err(err) => {
+        log::warn!("gamepads are unavailable: {}", err);
+        None
+      }
+    };
+
+    Self {
+      gilrs,
+      dpads: HashMap::new(),
+    }
+  }
+
+  /// Everything the pads did since the last call.
+  pub fn poll(&mut self) -> Vec<GamepadEvent> {
+    let mut events = Vec::new();
+    let Some(gilrs) = &mut self.gilrs else {
+      return events;
+    };
+    let dpads = &mut self.dpads;
+
+    while let Some(event) = gilrs.next_event() {
+      let pad = usize::from(event.id) as u32;
+
+      match event.event {
+        EventType::ButtonPressed(button, _) => {
+          events.extend(button_event(dpads, event.id, button, ButtonState::Press))
+        }
+        EventType::ButtonReleased(button, _) => {
+          events.extend(button_event(dpads, event.id, button, ButtonState::Release))
+        }
+        EventType::AxisChanged(axis, position, _) => {
+          if let Some(axis) = axis_index(axis) {
+            events.push(GamepadEvent::Axis(ControllerAxisArgs {
+              id: pad,
+              axis,
+              position: position as f64,
+            }));
+          }
+        }
+        EventType::Disconnected => {
+          dpads.remove(&event.id);
+          events.push(GamepadEvent::Disconnected(pad));
+        }
+        _ => {}
+      }
+    }
+
+    events
+  }
+}
+
+fn button_event(
+  dpads: &mut HashMap<GamepadId, [bool; 4]>,
+  id: GamepadId,
+  button: gilrs::Button,
+  state: ButtonState,
+) -> Option<GamepadEvent> {
+  let pad = usize::from(id) as u32;
+  let button = match dpad_index(button) {
+    Some(direction) => {
+      let dpad = dpads.entry(id).or_default();
+      dpad[direction] = state == ButtonState::Press;
+
+      Button::Hat(ControllerHat {
+        id: pad,
+        state: hat_state(*dpad),
+        which: 0,
+      })
+    }
+    None => Button::Controller(ControllerButton {
+      id: pad,
+      button: button_index(button)?,
+    }),
+  };
+
+  // the hat reports every change as a press, `Centered` included
+  let state = match button {
+    Button::Hat(_) => ButtonState::Press,
+    _ => state,
+  };
+
+  Some(GamepadEvent::Button(ButtonArgs {
+    state,
+    button,
+    scancode: None,
+  }))
+}
+
+/// Button numbers follow the usual SDL layout, so `GamepadButton(0)` is the bottom face button.
+fn button_index(button: gilrs::Button) -> Option<u8> {
+  use gilrs::Button::*;
+
+  match button {
+    South => Some(0),
+    East => Some(1),
+    West => Some(2),
+    North => Some(3),
+    LeftTrigger => Some(4),
+    RightTrigger => Some(5),
+    Select => Some(6),
+    Start => Some(7),
+    Mode => Some(8),
+    LeftThumb => Some(9),
+    RightThumb => Some(10),
+    _ => None,
+  }
+}
+
+fn dpad_index(button: gilrs::Button) -> Option<usize> {
+  use gilrs::Button::*;
+
+  match button {
+    DPadUp => Some(0),
+    DPadDown => Some(1),
+    DPadLeft => Some(2),
+    DPadRight => Some(3),
+    _ => None,
+  }
+}
+
+fn hat_state([up, down, left, right]: [bool; 4]) -> HatState {
+  match (up && !down, down && !up, left && !right, right && !left) {
+    (true, _, true, _) => HatState::LeftUp,
+    (true, _, _, true) => HatState::RightUp,
+    (_, true, true, _) => HatState::LeftDown,
+    (_, true, _, true) => HatState::RightDown,
+    (true, ..) => HatState::Up,
+    (_, true, ..) => HatState::Down,
+    (_, _, true, _) => HatState::Left,
+    (.., true) => HatState::Right,
+    _ => HatState::Centered,
+  }
+}
+
+fn axis_index(axis: Axis) -> Option<u8> {
+  match axis {
+    Axis::LeftStickX => Some(0),
+    Axis::LeftStickY => Some(1),
+    Axis::RightStickX => Some(2),
+    Axis::RightStickY => Some(3),
+    Axis::LeftZ => Some(4),
+    Axis::RightZ => Some(5),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn dpad_buttons_combine_into_one_hat() {
+    assert_eq!(hat_state([false; 4]), HatState::Centered);
+    assert_eq!(hat_state([true, false, true, false]), HatState::LeftUp);
+    assert_eq!(hat_state([false, true, false, false]), HatState::Down);
+    // opposite directions cancel out
+    assert_eq!(hat_state([true, true, false, true]), HatState::Right);
+  }
+}
@@ -0,0 +1,190 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::storage::{self, Versioned};
+
+/// Bumped whenever the layout of the high score file changes.
+pub const HIGHSCORES_VERSION: u32 = 1;
+pub const HIGHSCORES_CAPACITY: usize = 10;
+pub const HIGHSCORES_FILE_NAME: &str = "highscores.ron";
+pub const NAME_LENGTH: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScore {
+  pub name: String,
+  pub score: u64,
+  pub rounds: u32,
+  pub date: String,
+}
+
+/// The local top ten, kept in the user data directory between runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScores {
+  version: u32,
+  pub entries: Vec<HighScore>,
+}
+
+impl Default for HighScores {
+  fn default() -> Self {
+    Self {
+      version: HIGHSCORES_VERSION,
+      entries: Vec::new(),
+    }
+  }
+}
+
+impl Versioned for HighScores {
+  const VERSION: u32 = HIGHSCORES_VERSION;
+  const NAME: &'static str = "high score";
+
+  fn version(&self) -> u32 {
+    self.version
+  }
+}
+
+impl HighScores {
+  pub fn path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("rusty-invaders").join(HIGHSCORES_FILE_NAME))
+  }
+
+  /// Reads the saved table, starting from an empty one when there is none or it can't be read.
+  pub fn load() -> Self {
+    storage::read_or_default(HighScores::path())
+  }
+
+  pub fn save(&self) -> Result<(), String> {
+    let path = HighScores::path().ok_or("no user data directory")?;
+    storage::write(&path, self)
+  }
+
+  pub fn qualifies(&self, score: u64) -> bool {
+    if score == 0 {
+      return false;
+    }
+
+    match self.entries.get(HIGHSCORES_CAPACITY - 1) {
+      Some(last) => score > last.score,
+      None => true,
+    }
+  }
+
+  /// Adds the score in its place and drops whatever falls off the bottom, returns its rank.
+  pub fn insert(&mut self, name: &str, score: u64, rounds: u32) -> usize {
+    let rank = self.entries.partition_point(|entry| entry.score >= score);
+
+    self.entries.insert(
+      rank,
+      HighScore {
+        name: name.to_string(),
+        score,
+        rounds,
+        date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+      },
+    );
+    self.entries.truncate(HIGHSCORES_CAPACITY);
+
+    rank
+  }
+}
+
+/// The initials typed in after a game that made it into the table.
+#[derive(Debug, Clone)]
+pub struct NameEntry {
+  pub name: String,
+  pub score: u64,
+  pub rounds: u32,
+}
+
+impl NameEntry {
+  pub fn new(score: u64, rounds: u32) -> Self {
+    Self {
+      name: String::new(),
+      score,
+      rounds,
+    }
+  }
+
+  pub fn type_text(&mut self, text: &str) {
+    for c in text.chars().filter(|c| c.is_ascii_alphanumeric()) {
+      if self.name.len() < NAME_LENGTH {
+        self.name.push(c.to_ascii_uppercase());
+      }
+    }
+  }
+
+  pub fn erase(&mut self) {
+    self.name.pop();
+  }
+
+  /// Initials are always the full three letters, like on the arcade cabinet.
+  pub fn is_complete(&self) -> bool {
+    self.name.len() == NAME_LENGTH
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn table(scores: &[u64]) -> HighScores {
+    let mut table = HighScores::default();
+    for score in scores {
+      table.insert("AAA", *score, 1);
+    }
+
+    table
+  }
+
+  fn scores(table: &HighScores) -> Vec<u64> {
+    table.entries.iter().map(|entry| entry.score).collect()
+  }
+
+  #[test]
+  fn scores_are_ranked_highest_first() {
+    let mut table = table(&[300, 100, 200]);
+
+    assert_eq!(table.insert("BBB", 150, 2), 2);
+    assert_eq!(scores(&table), vec![300, 200, 150, 100]);
+  }
+
+  #[test]
+  fn ties_rank_below_the_earlier_score() {
+    let mut table = table(&[200, 100]);
+
+    assert_eq!(table.insert("BBB", 200, 2), 1);
+    assert_eq!(table.entries[0].name, "AAA");
+    assert_eq!(table.entries[1].name, "BBB");
+  }
+
+  #[test]
+  fn the_table_keeps_the_top_ten() {
+    let mut table = table(&[10, 20, 30, 40, 50, 60, 70, 80, 90, 100]);
+
+    assert!(!table.qualifies(10));
+    assert!(table.qualifies(11));
+
+    table.insert("BBB", 55, 2);
+    assert_eq!(table.entries.len(), HIGHSCORES_CAPACITY);
+    assert_eq!(table.entries.last().unwrap().score, 20);
+  }
+
+  #[test]
+  fn a_score_of_zero_never_qualifies() {
+    assert!(!HighScores::default().qualifies(0));
+    assert!(HighScores::default().qualifies(1));
+  }
+
+  #[test]
+  fn initials_need_all_three_letters() {
+    let mut entry = NameEntry::new(100, 1);
+
+    entry.type_text("a-b");
+    assert_eq!(entry.name, "AB");
+    assert!(!entry.is_complete());
+
+    entry.type_text("cd");
+    assert_eq!(entry.name, "ABC");
+    assert!(entry.is_complete());
+  }
+}
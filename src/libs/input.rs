@@ -0,0 +1,450 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use piston::{Button, ButtonArgs, ButtonState, ControllerAxisArgs, HatState, Key, MouseButton};
+use serde::{Deserialize, Serialize};
+
+/// What the player wants to do, independent of the device and key that asked for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+  MoveLeft,
+  MoveRight,
+  Fire,
+  Restart,
+  Pause,
+  /// Not part of the game, handled before the inputs reach it and never recorded.
+  Mute,
+}
+
+impl Action {
+  pub const ALL: [Action; 6] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Fire,
+    Action::Restart,
+    Action::Pause,
+    Action::Mute,
+  ];
+}
+
+/// A single physical input an action can be bound to. Gamepad bindings match every connected pad,
+/// so a controller plugged in mid-game works straight away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+  Keyboard(Key),
+  Mouse(MouseButton),
+  GamepadButton(u8),
+  DPad(DPad),
+  Stick(u8, AxisDirection),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DPad {
+  Up,
+  Down,
+  Left,
+  Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AxisDirection {
+  Negative,
+  Positive,
+}
+
+impl DPad {
+  const ALL: [DPad; 4] = [DPad::Up, DPad::Down, DPad::Left, DPad::Right];
+
+  fn is_part_of(&self, hat: HatState) -> bool {
+    matches!(
+      (self, hat),
+      (
+        DPad::Up,
+        HatState::Up | HatState::LeftUp | HatState::RightUp
+      ) | (
+        DPad::Down,
+        HatState::Down | HatState::LeftDown | HatState::RightDown
+      ) | (
+        DPad::Left,
+        HatState::Left | HatState::LeftUp | HatState::LeftDown
+      ) | (
+        DPad::Right,
+        HatState::Right | HatState::RightUp | HatState::RightDown
+      )
+    )
+  }
+}
+
+/// Inputs bound to each action, any of them triggers it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bindings {
+  pub move_left: Vec<Binding>,
+  pub move_right: Vec<Binding>,
+  pub fire: Vec<Binding>,
+  pub restart: Vec<Binding>,
+  pub pause: Vec<Binding>,
+  pub mute: Vec<Binding>,
+  /// How far a stick has to be pushed before it counts, from 0 to 1.
+  pub deadzone: f64,
+}
+
+impl Default for Bindings {
+  fn default() -> Self {
+    Self {
+      move_left: vec![
+        Binding::Keyboard(Key::Left),
+        Binding::DPad(DPad::Left),
+        Binding::Stick(0, AxisDirection::Negative),
+      ],
+      move_right: vec![
+        Binding::Keyboard(Key::Right),
+        Binding::DPad(DPad::Right),
+        Binding::Stick(0, AxisDirection::Positive),
+      ],
+      fire: vec![Binding::Keyboard(Key::Space), Binding::GamepadButton(0)],
+      restart: vec![Binding::Keyboard(Key::R), Binding::GamepadButton(7)],
+      pause: vec![Binding::Keyboard(Key::P), Binding::GamepadButton(6)],
+      mute: vec![Binding::Keyboard(Key::M)],
+      deadzone: 0.3,
+    }
+  }
+}
+
+impl Bindings {
+  pub fn inputs(&self, action: Action) -> &[Binding] {
+    match action {
+      Action::MoveLeft => &self.move_left,
+      Action::MoveRight => &self.move_right,
+      Action::Fire => &self.fire,
+      Action::Restart => &self.restart,
+      Action::Pause => &self.pause,
+      Action::Mute => &self.mute,
+    }
+  }
+
+  pub fn actions(&self, binding: Binding) -> impl Iterator<Item = Action> + '_ {
+    Action::ALL
+      .into_iter()
+      .filter(move |action| self.inputs(*action).contains(&binding))
+  }
+}
+
+/// Tracks which inputs are being held and which of them went down during the current tick.
+#[derive(Debug, Clone)]
+pub struct InputState<T: Copy + Eq + Hash> {
+  held: HashSet<T>,
+  pressed: HashSet<T>,
+}
+
+impl<T: Copy + Eq + Hash> Default for InputState<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Copy + Eq + Hash> InputState<T> {
+  pub fn new() -> Self {
+    Self {
+      held: HashSet::new(),
+      pressed: HashSet::new(),
+    }
+  }
+
+  /// Returns whether the input actually changed state.
+  pub fn apply(&mut self, input: T, state: ButtonState) -> bool {
+    match state {
+      ButtonState::Press => self.press(input),
+      ButtonState::Release => self.release(input),
+    }
+  }
+
+  pub fn press(&mut self, input: T) -> bool {
+    // key repeat sends presses for a button that is already down
+    let changed = self.held.insert(input);
+    if changed {
+      self.pressed.insert(input);
+    }
+
+    changed
+  }
+
+  pub fn release(&mut self, input: T) -> bool {
+    self.held.remove(&input)
+  }
+
+  /// The input went down during this tick.
+  pub fn pressed(&self, input: T) -> bool {
+    self.pressed.contains(&input)
+  }
+
+  /// The input is currently down, no matter when it was pressed.
+  pub fn held(&self, input: T) -> bool {
+    self.held.contains(&input)
+  }
+
+  /// Forgets the presses of the tick that was just simulated.
+  pub fn end_tick(&mut self) {
+    self.pressed.clear();
+  }
+}
+
+/// Turns raw keyboard, mouse and gamepad events into action changes through the bindings table.
+pub struct ActionMapper {
+  bindings: Bindings,
+  /// Held inputs along with the pad they came from, `None` for the keyboard and mouse.
+  inputs: InputState<(Option<u32>, Binding)>,
+}
+
+impl ActionMapper {
+  pub fn new(bindings: Bindings) -> Self {
+    Self {
+      bindings,
+      inputs: InputState::new(),
+    }
+  }
+
+  pub fn on_button(&mut self, args: &ButtonArgs) -> Vec<(Action, ButtonState)> {
+    let mut changes = Vec::new();
+    let pressed = args.state == ButtonState::Press;
+
+    match args.button {
+      Button::Hat(hat) => {
+        for direction in DPad::ALL {
+          let held = pressed && direction.is_part_of(hat.state);
+          self.set(Some(hat.id), Binding::DPad(direction), held, &mut changes);
+        }
+      }
+      Button::Controller(controller) => {
+        let binding = Binding::GamepadButton(controller.button);
+        self.set(Some(controller.id), binding, pressed, &mut changes);
+      }
+      Button::Keyboard(key) => self.set(None, Binding::Keyboard(key), pressed, &mut changes),
+      Button::Mouse(mouse) => self.set(None, Binding::Mouse(mouse), pressed, &mut changes),
+    }
+
+    changes
+  }
+
+  /// Sticks act like a pair of buttons, one per direction, once pushed past the deadzone.
+  pub fn on_axis(&mut self, args: &ControllerAxisArgs) -> Vec<(Action, ButtonState)> {
+    let mut changes = Vec::new();
+    let deadzone = self.bindings.deadzone;
+
+    self.set(
+      Some(args.id),
+      Binding::Stick(args.axis, AxisDirection::Negative),
+      args.position < -deadzone,
+      &mut changes,
+    );
+    self.set(
+      Some(args.id),
+      Binding::Stick(args.axis, AxisDirection::Positive),
+      args.position > deadzone,
+      &mut changes,
+    );
+
+    changes
+  }
+
+  /// Lets go of everything, for when the window loses focus and releases may never arrive.
+  pub fn release_all(&mut self) -> Vec<(Action, ButtonState)> {
+    self.release_where(|_| true)
+  }
+
+  /// Lets go of everything held on a pad that was unplugged.
+  pub fn on_disconnect(&mut self, pad: u32) -> Vec<(Action, ButtonState)> {
+    self.release_where(|from| from == Some(pad))
+  }
+
+  fn release_where(&mut self, from: impl Fn(Option<u32>) -> bool) -> Vec<(Action, ButtonState)> {
+    let mut changes = Vec::new();
+    let held: Vec<(Option<u32>, Binding)> = self
+      .inputs
+      .held
+      .iter()
+      .filter(|(pad, _)| from(*pad))
+      .copied()
+      .collect();
+
+    for (pad, binding) in held {
+      self.set(pad, binding, false, &mut changes);
+    }
+
+    changes
+  }
+
+  /// An action bound to several inputs, or held on several pads, stays held until the last of
+  /// them is released.
+  fn set(
+    &mut self,
+    pad: Option<u32>,
+    binding: Binding,
+    held: bool,
+    changes: &mut Vec<(Action, ButtonState)>,
+  ) {
+    let state = if held {
+      ButtonState::Press
+    } else {
+      ButtonState::Release
+    };
+
+    if !self.inputs.apply((pad, binding), state) {
+      return;
+    }
+
+    for action in self.bindings.actions(binding) {
+      let bound = self.bindings.inputs(action);
+      let others_held = self
+        .inputs
+        .held
+        .iter()
+        .any(|input| *input != (pad, binding) && bound.contains(&input.1));
+
+      if !others_held {
+        changes.push((action, state));
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use piston::ControllerHat;
+
+  fn mapper() -> ActionMapper {
+    ActionMapper::new(Bindings::default())
+  }
+
+  fn key(key: Key, state: ButtonState) -> ButtonArgs {
+    ButtonArgs {
+      state,
+      button: Button::Keyboard(key),
+      scancode: None,
+    }
+  }
+
+  fn hat(state: HatState) -> ButtonArgs {
+    ButtonArgs {
+      state: ButtonState::Press,
+      button: Button::Hat(ControllerHat {
+        id: 0,
+        state,
+        which: 0,
+      }),
+      scancode: None,
+    }
+  }
+
+  fn stick(id: u32, position: f64) -> ControllerAxisArgs {
+    ControllerAxisArgs {
+      id,
+      axis: 0,
+      position,
+    }
+  }
+
+  #[test]
+  fn sticks_only_count_past_the_deadzone() {
+    let mut mapper = mapper();
+    let deadzone = Bindings::default().deadzone;
+
+    assert!(mapper.on_axis(&stick(0, -deadzone)).is_empty());
+    assert_eq!(
+      mapper.on_axis(&stick(0, -deadzone - 0.01)),
+      vec![(Action::MoveLeft, ButtonState::Press)]
+    );
+    assert_eq!(
+      mapper.on_axis(&stick(0, -deadzone)),
+      vec![(Action::MoveLeft, ButtonState::Release)]
+    );
+    assert!(mapper.on_axis(&stick(0, deadzone)).is_empty());
+    assert_eq!(
+      mapper.on_axis(&stick(0, deadzone + 0.01)),
+      vec![(Action::MoveRight, ButtonState::Press)]
+    );
+  }
+
+  #[test]
+  fn diagonal_hat_releases_when_centered() {
+    let mut mapper = mapper();
+
+    assert_eq!(
+      mapper.on_button(&hat(HatState::LeftUp)),
+      vec![(Action::MoveLeft, ButtonState::Press)]
+    );
+    assert_eq!(
+      mapper.on_button(&hat(HatState::Centered)),
+      vec![(Action::MoveLeft, ButtonState::Release)]
+    );
+  }
+
+  #[test]
+  fn action_stays_held_until_every_input_is_released() {
+    let mut mapper = mapper();
+
+    assert_eq!(
+      mapper.on_button(&key(Key::Left, ButtonState::Press)),
+      vec![(Action::MoveLeft, ButtonState::Press)]
+    );
+    assert!(mapper.on_button(&hat(HatState::Left)).is_empty());
+    assert!(mapper
+      .on_button(&key(Key::Left, ButtonState::Release))
+      .is_empty());
+    assert_eq!(
+      mapper.on_button(&hat(HatState::Centered)),
+      vec![(Action::MoveLeft, ButtonState::Release)]
+    );
+  }
+
+  #[test]
+  fn pads_do_not_release_each_other() {
+    let mut mapper = mapper();
+
+    assert_eq!(
+      mapper.on_axis(&stick(0, -1.0)),
+      vec![(Action::MoveLeft, ButtonState::Press)]
+    );
+    assert!(mapper.on_axis(&stick(1, -1.0)).is_empty());
+    assert!(mapper.on_axis(&stick(1, 0.0)).is_empty());
+    assert_eq!(
+      mapper.on_axis(&stick(0, 0.0)),
+      vec![(Action::MoveLeft, ButtonState::Release)]
+    );
+  }
+
+  #[test]
+  fn unplugging_a_pad_releases_only_its_inputs() {
+    let mut mapper = mapper();
+    mapper.on_axis(&stick(0, -1.0));
+    mapper.on_axis(&stick(1, -1.0));
+    mapper.on_button(&key(Key::Space, ButtonState::Press));
+
+    assert!(mapper.on_disconnect(0).is_empty());
+    assert_eq!(
+      mapper.on_disconnect(1),
+      vec![(Action::MoveLeft, ButtonState::Release)]
+    );
+    assert!(mapper.on_disconnect(1).is_empty());
+  }
+
+  #[test]
+  fn release_all_lets_go_of_everything_once() {
+    let mut mapper = mapper();
+    mapper.on_button(&key(Key::Left, ButtonState::Press));
+    mapper.on_button(&key(Key::Space, ButtonState::Press));
+
+    let mut changes = mapper.release_all();
+    changes.sort_by_key(|(action, _)| format!("{:?}", action));
+
+    assert_eq!(
+      changes,
+      vec![
+        (Action::Fire, ButtonState::Release),
+        (Action::MoveLeft, ButtonState::Release),
+      ]
+    );
+    assert!(mapper.release_all().is_empty());
+  }
+}
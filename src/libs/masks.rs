@@ -0,0 +1,277 @@
+use std::path::Path;
+
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+
+use crate::components::*;
+use crate::entities::EnemyVariation;
+
+use super::assets::*;
+use super::config::CollisionConfig;
+
+// pixels at least this opaque count as solid
+const ALPHA_THRESHOLD: u8 = 128;
+
+/// How an entity type decides it was hit, once the bounding boxes already overlap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CollisionShape {
+  /// The whole bounding box counts.
+  Rect,
+  /// Only the opaque pixels of the sprite count.
+  Mask,
+}
+
+/// Which pixels of a sprite are solid, read from its alpha channel.
+#[derive(Debug, Clone)]
+pub struct Mask {
+  width: u32,
+  height: u32,
+  bits: Vec<bool>,
+}
+
+impl Mask {
+  pub fn from_image(image: &RgbaImage) -> Self {
+    Self {
+      width: image.width(),
+      height: image.height(),
+      bits: image
+        .pixels()
+        .map(|pixel| pixel[3] >= ALPHA_THRESHOLD)
+        .collect(),
+    }
+  }
+
+  /// Whether the sprite is solid at the world point `(x, y)` once stretched over `transform`.
+  pub fn is_solid_at(&self, transform: &Transform, x: f64, y: f64) -> bool {
+    let u = (x - transform.x) / transform.width * self.width as f64;
+    let v = (y - transform.y) / transform.height * self.height as f64;
+    if u < 0.0 || v < 0.0 || u > self.width as f64 || v > self.height as f64 || self.bits.is_empty()
+    {
+      return false;
+    }
+
+    // the far edges belong to the last pixel
+    let col = (u as u32).min(self.width - 1);
+    let row = (v as u32).min(self.height - 1);
+
+    self.bits[(row * self.width + col) as usize]
+  }
+}
+
+/// The masks of every sprite that has one, an entity without a mask collides as a rectangle.
+#[derive(Debug, Clone, Default)]
+pub struct CollisionMasks {
+  pub player: Option<Mask>,
+  pub bullet: Option<Mask>,
+  pub saucer: Option<Mask>,
+  pub crab: Option<Mask>,
+  pub squid: Option<Mask>,
+  pub octopus: Option<Mask>,
+}
+
+impl CollisionMasks {
+  /// Reads the alpha channel of every sprite in `assets_folder`. Only the image files are
+  /// touched, no GL context needed, a sprite that fails to load collides as a rectangle.
+  pub fn load(assets_folder: &Path) -> Self {
+    let images = assets_folder.join("images");
+    let mask = |name: &str| {
+      image::open(images.join(name))
+        .ok()
+        .map(|image| Mask::from_image(&image.to_rgba8()))
+    };
+
+    Self {
+      player: mask(PLAYER_TEXTURE_NAME),
+      bullet: mask(BULLET_TEXTURE_NAME),
+      saucer: mask(SAUCER_TEXTURE_NAME),
+      crab: mask(CRAB_TEXTURE_NAME),
+      squid: mask(SQUID_TEXTURE_NAME),
+      octopus: mask(OCTOPUS_TEXTURE_NAME),
+    }
+  }
+
+  /// The masks of the sprites shipped in the repository's `assets` folder.
+  #[cfg(test)]
+  pub fn bundled() -> Self {
+    CollisionMasks::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("assets"))
+  }
+
+  /// Drops the masks of entity types configured to collide as plain rectangles.
+  pub fn select(mut self, shapes: &CollisionConfig) -> Self {
+    let keep = |mask: &mut Option<Mask>, shape: CollisionShape| {
+      if shape == CollisionShape::Rect {
+        *mask = None;
+      }
+    };
+
+    keep(&mut self.player, shapes.player);
+    keep(&mut self.bullet, shapes.bullet);
+    keep(&mut self.saucer, shapes.saucer);
+    keep(&mut self.crab, shapes.enemies);
+    keep(&mut self.squid, shapes.enemies);
+    keep(&mut self.octopus, shapes.enemies);
+
+    self
+  }
+
+  pub fn enemy(&self, variation: EnemyVariation) -> Option<&Mask> {
+    match variation {
+      EnemyVariation::Crab => self.crab.as_ref(),
+      EnemyVariation::Squid => self.squid.as_ref(),
+      EnemyVariation::Octopus => self.octopus.as_ref(),
+    }
+  }
+}
+
+/// Narrowphase for two boxes that may overlap, `None` masks count as solid all over. Samples
+/// the overlap one world unit at a time.
+pub fn overlaps(
+  a: &Transform,
+  a_mask: Option<&Mask>,
+  b: &Transform,
+  b_mask: Option<&Mask>,
+) -> bool {
+  if !a.collides(b) {
+    return false;
+  }
+  if a_mask.is_none() && b_mask.is_none() {
+    return true;
+  }
+
+  let (left, right) = (a.x().max(b.x()), a.xw().min(b.xw()));
+  let (top, bottom) = (a.y().max(b.y()), a.yh().min(b.yh()));
+
+  let solid = |mask: Option<&Mask>, transform: &Transform, x: f64, y: f64| match mask {
+    Some(mask) => mask.is_solid_at(transform, x, y),
+    None => true,
+  };
+
+  samples(left, right)
+    .any(|x| samples(top, bottom).any(|y| solid(a_mask, a, x, y) && solid(b_mask, b, x, y)))
+}
+
+/// Like [`Collider::sweep`] for something that moved from `previous` to `current`, but only
+/// counting the first moment the solid pixels of both actually touch.
+pub fn sweep(
+  previous: &Transform,
+  current: &Transform,
+  mask: Option<&Mask>,
+  target: &Transform,
+  target_mask: Option<&Mask>,
+) -> Option<f64> {
+  let enter = current.sweep(previous, target)?;
+  if mask.is_none() && target_mask.is_none() {
+    return Some(enter);
+  }
+
+  // one sample per world unit travelled after first contact
+  let (dx, dy) = (current.x - previous.x, current.y - previous.y);
+  let distance = (dx * dx + dy * dy).sqrt() * (1.0 - enter);
+  let steps = distance.ceil().max(1.0) as u32;
+
+  (0..=steps)
+    .map(|step| enter + (1.0 - enter) * step as f64 / steps as f64)
+    .find(|time| {
+      let at = Transform {
+        x: previous.x + dx * time,
+        y: previous.y + dy * time,
+        ..*current
+      };
+      overlaps(&at, mask, target, target_mask)
+    })
+}
+
+// pixel centres between `from` and `to`, or the middle when they are less than a unit apart
+fn samples(from: f64, to: f64) -> impl Iterator<Item = f64> {
+  let count = (to - from).floor().max(0.0) as u32;
+  let middle = (from + to) / 2.0;
+
+  (0..count)
+    .map(move |i| from + i as f64 + 0.5)
+    .chain((count == 0).then_some(middle))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::Rgba;
+
+  /// Opaque except for the top half, like a sprite with empty space above it.
+  fn bottom_half(width: u32, height: u32) -> Mask {
+    let image = RgbaImage::from_fn(width, height, |_, y| {
+      Rgba([255, 255, 255, if y >= height / 2 { 255 } else { 0 }])
+    });
+    Mask::from_image(&image)
+  }
+
+  /// A 5×10 bullet with no mask fired through a 30×22 invader whose top half is transparent.
+  fn sweep_masked(previous: (f64, f64), current: (f64, f64)) -> Option<f64> {
+    let enemy = Transform::new(100.0, 100.0, 30.0, 22.0);
+    let mask = bottom_half(30, 22);
+
+    sweep(
+      &Transform::new(previous.0, previous.1, 5.0, 10.0),
+      &Transform::new(current.0, current.1, 5.0, 10.0),
+      None,
+      &enemy,
+      Some(&mask),
+    )
+  }
+
+  #[test]
+  fn every_bundled_sprite_has_a_mask() {
+    let masks = CollisionMasks::bundled();
+
+    for mask in [
+      &masks.player,
+      &masks.bullet,
+      &masks.saucer,
+      &masks.crab,
+      &masks.squid,
+      &masks.octopus,
+    ] {
+      assert!(mask.as_ref().is_some_and(|mask| mask.bits.contains(&true)));
+    }
+  }
+
+  #[test]
+  fn rect_shapes_drop_their_masks() {
+    let shapes = CollisionConfig {
+      enemies: CollisionShape::Rect,
+      ..CollisionConfig::default()
+    };
+    let masks = CollisionMasks::bundled().select(&shapes);
+
+    assert!(masks.player.is_some());
+    assert!(masks.enemy(EnemyVariation::Crab).is_none());
+    assert!(masks.enemy(EnemyVariation::Octopus).is_none());
+  }
+
+  #[test]
+  fn catches_extreme_steps_on_masks() {
+    assert_hit(sweep_masked((112.0, 600.0), (112.0, -400.0)));
+    assert_hit(sweep_masked((112.0, 500_100.0), (112.0, -499_900.0)));
+  }
+
+  #[test]
+  fn catches_diagonal_steps_on_masks() {
+    assert_hit(sweep_masked((-90.0, 300.0), (310.0, -100.0)));
+  }
+
+  #[test]
+  fn starting_inside_the_solid_part_hits_straight_away() {
+    assert_eq!(sweep_masked((112.0, 115.0), (112.0, -900.0)), Some(0.0));
+  }
+
+  #[test]
+  fn transparent_pixels_are_passed_through() {
+    // falling down into the transparent top half and stopping there
+    assert_eq!(sweep_masked((112.0, 50.0), (112.0, 95.0)), None);
+  }
+
+  #[test]
+  fn misses_targets_behind_the_start_or_past_the_end() {
+    assert_eq!(sweep_masked((112.0, 50.0), (112.0, -950.0)), None);
+    assert_eq!(sweep_masked((112.0, 600.0), (112.0, 300.0)), None);
+  }
+}
@@ -0,0 +1,92 @@
+use kira::{
+  manager::{backend::DefaultBackend, AudioManager, AudioManagerSettings},
+  sound::static_sound::{StaticSoundData, StaticSoundHandle},
+  track::{TrackBuilder, TrackHandle},
+  tween::Tween,
+};
+
+use super::assets::AssetError;
+use super::settings::AudioSettings;
+
+/// The mixer track a sound is routed through, each one has its own volume in the settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bus {
+  Music,
+  Sfx,
+  Ui,
+}
+
+/// The audio device with one kira sub-track per [`Bus`], all feeding the main track which
+/// carries the master volume and the mute.
+pub struct Mixer {
+  manager: AudioManager,
+  main: TrackHandle,
+  music: TrackHandle,
+  sfx: TrackHandle,
+  ui: TrackHandle,
+}
+
+impl Mixer {
+  pub fn new(settings: &AudioSettings) -> Result<Self, AssetError> {
+    let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())
+      .map_err(|err| AssetError::Audio(err.to_string()))?;
+
+    let mut track = |volume: f64| {
+      manager
+        .add_sub_track(TrackBuilder::new().volume(volume))
+        .map_err(|err| AssetError::Audio(err.to_string()))
+    };
+
+    let music = track(settings.music)?;
+    let sfx = track(settings.sfx)?;
+    let ui = track(settings.ui)?;
+
+    let mixer = Self {
+      main: manager.main_track(),
+      manager,
+      music,
+      sfx,
+      ui,
+    };
+    mixer.apply(settings);
+
+    Ok(mixer)
+  }
+
+  /// Starts `data` on the track of `bus`.
+  pub fn play(
+    &mut self,
+    bus: Bus,
+    mut data: StaticSoundData,
+  ) -> Result<StaticSoundHandle, AssetError> {
+    data.settings = data.settings.output_destination(self.track(bus));
+
+    self
+      .manager
+      .play(data)
+      .map_err(|err| AssetError::Audio(err.to_string()))
+  }
+
+  /// Brings every track in line with `settings`.
+  pub fn apply(&self, settings: &AudioSettings) {
+    let master = if settings.muted { 0.0 } else { settings.master };
+
+    for (track, volume) in [
+      (&self.main, master),
+      (&self.music, settings.music),
+      (&self.sfx, settings.sfx),
+      (&self.ui, settings.ui),
+    ] {
+      // only fails when the audio thread is gone, and then there is nothing to turn down
+      let _ = track.set_volume(volume, Tween::default());
+    }
+  }
+
+  fn track(&self, bus: Bus) -> &TrackHandle {
+    match bus {
+      Bus::Music => &self.music,
+      Bus::Sfx => &self.sfx,
+      Bus::Ui => &self.ui,
+    }
+  }
+}
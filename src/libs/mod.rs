@@ -1,8 +1,22 @@
+pub mod arena;
 pub mod assets;
+pub mod audio;
+pub mod cli;
+pub mod clock;
 pub mod config;
-pub mod intervals;
+pub mod gamepads;
+pub mod highscores;
+pub mod input;
+pub mod masks;
+pub mod mixer;
+pub mod music;
+pub mod settings;
+pub mod sound_cache;
 pub mod sounds;
+pub mod spatial;
+pub mod storage;
 pub mod textures;
+pub mod voices;
 
 pub mod utils {
   use cgmath::Vector2;
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use kira::{
+  sound::static_sound::{StaticSoundHandle, StaticSoundSettings},
+  tween::Tween,
+};
+
+use super::assets::*;
+use super::config::config;
+use super::mixer::{Bus, Mixer};
+use super::sound_cache::SoundCache;
+
+const CROSSFADE: Duration = Duration::from_secs(1);
+
+// seconds between march steps with a full grid and with a single invader left
+const MARCH_SLOWEST: f64 = 0.9;
+const MARCH_FASTEST: f64 = 0.12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Music {
+  Title,
+  Gameplay,
+  GameOver,
+}
+
+impl Music {
+  /// The looping file behind the track, gameplay is the march instead.
+  fn as_filename(&self) -> Option<&'static str> {
+    match self {
+      Music::Title => Some(TITLE_MUSIC_NAME),
+      Music::Gameplay => None,
+      Music::GameOver => Some(GAME_OVER_MUSIC_NAME),
+    }
+  }
+}
+
+/// Background music: looping tracks for the menus and the arcade's four-note bass march while
+/// playing, which speeds up as the invaders thin out. Switching tracks crossfades them.
+pub struct MusicPlayer {
+  current: Option<Music>,
+  tracks: HashMap<Music, StaticSoundHandle>,
+  cache: SoundCache<&'static str>,
+  march: March,
+}
+
+struct March {
+  /// Fades towards 1 while the march is the current track and back to 0 after.
+  gain: f64,
+  until_step: f64,
+  note: usize,
+}
+
+impl March {
+  /// Seconds until the next note, shrinking linearly with the invaders left.
+  fn interval(enemies_left: usize, enemies_total: usize) -> f64 {
+    let remaining = enemies_left as f64 / enemies_total.max(1) as f64;
+    MARCH_FASTEST + (MARCH_SLOWEST - MARCH_FASTEST) * remaining
+  }
+}
+
+impl MusicPlayer {
+  pub fn new(assets: &Path) -> Self {
+    Self {
+      current: None,
+      tracks: HashMap::new(),
+      cache: SoundCache::new(assets.join("sounds")),
+      march: March {
+        gain: 0.0,
+        until_step: 0.0,
+        note: 0,
+      },
+    }
+  }
+
+  /// Crossfades to `music`, nothing happens if it is already playing.
+  pub fn play(&mut self, mixer: &mut Mixer, music: Music) {
+    if self.current == Some(music) {
+      return;
+    }
+    self.current = Some(music);
+
+    let fade = Tween {
+      duration: CROSSFADE,
+      ..Default::default()
+    };
+
+    for (_, mut handle) in self.tracks.drain() {
+      // a track that can't be stopped is already gone
+      let _ = handle.stop(fade);
+    }
+
+    let filename = match music.as_filename() {
+      Some(filename) => filename,
+      None => {
+        self.march.until_step = 0.0;
+        return;
+      }
+    };
+
+    let settings = StaticSoundSettings::new()
+      .loop_region(..)
+      .volume(config().music.volume)
+      .fade_in_tween(fade);
+
+    if let Some(data) = self.cache.load(filename, filename) {
+      match mixer.play(Bus::Music, data.with_settings(settings)) {
+        Ok(handle) => {
+          self.tracks.insert(music, handle);
+        }
+        Err(err) => log::debug!("{}", err),
+      }
+    }
+  }
+
+  /// Steps the march, `enemies_left` out of `enemies_total` sets the tempo.
+  pub fn update(&mut self, mixer: &mut Mixer, dt: f64, enemies_left: usize, enemies_total: usize) {
+    let target = if self.current == Some(Music::Gameplay) {
+      1.0
+    } else {
+      0.0
+    };
+    let fade_step = dt / CROSSFADE.as_secs_f64();
+    self.march.gain = if self.march.gain < target {
+      (self.march.gain + fade_step).min(target)
+    } else {
+      (self.march.gain - fade_step).max(target)
+    };
+
+    if self.march.gain <= 0.0 || enemies_left == 0 {
+      return;
+    }
+
+    self.march.until_step -= dt;
+    if self.march.until_step > 0.0 {
+      return;
+    }
+
+    self.march.until_step = March::interval(enemies_left, enemies_total);
+
+    let note = MARCH_NOTE_NAMES[self.march.note];
+    self.march.note = (self.march.note + 1) % MARCH_NOTE_NAMES.len();
+
+    let volume = config().music.volume * self.march.gain;
+    if let Some(data) = self.cache.load(note, note) {
+      let settings = StaticSoundSettings::new().volume(volume);
+      if let Err(err) = mixer.play(Bus::Music, data.with_settings(settings)) {
+        log::debug!("{}", err);
+      }
+    }
+  }
+
+  /// Holds the looping track while the game is paused, the march stops on its own.
+  pub fn set_paused(&mut self, paused: bool) {
+    let fade = Tween::default();
+
+    for handle in self.tracks.values_mut() {
+      let _ = if paused {
+        handle.pause(fade)
+      } else {
+        handle.resume(fade)
+      };
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn the_march_speeds_up_as_invaders_die() {
+    assert_eq!(March::interval(50, 50), MARCH_SLOWEST);
+    assert!(March::interval(25, 50) < MARCH_SLOWEST);
+    assert!(March::interval(1, 50) < March::interval(25, 50));
+    assert!(March::interval(1, 50) > MARCH_FASTEST);
+  }
+}
@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::storage::{self, Versioned};
+
+/// Bumped whenever the layout of the settings file changes.
+pub const SETTINGS_VERSION: u32 = 1;
+pub const SETTINGS_FILE_NAME: &str = "settings.ron";
+
+/// Player preferences changed from inside the game, as opposed to the tuning in `config.ron`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+  version: u32,
+  pub audio: AudioSettings,
+}
+
+/// Volume of every bus from 0 to 1, the master volume scales all of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+  pub master: f64,
+  pub music: f64,
+  pub sfx: f64,
+  pub ui: f64,
+  pub muted: bool,
+}
+
+impl Default for Settings {
+  fn default() -> Self {
+    Self {
+      version: SETTINGS_VERSION,
+      audio: AudioSettings::default(),
+    }
+  }
+}
+
+impl Default for AudioSettings {
+  fn default() -> Self {
+    Self {
+      master: 1.0,
+      music: 1.0,
+      sfx: 1.0,
+      ui: 1.0,
+      muted: false,
+    }
+  }
+}
+
+impl Versioned for Settings {
+  const VERSION: u32 = SETTINGS_VERSION;
+  const NAME: &'static str = "settings";
+
+  fn version(&self) -> u32 {
+    self.version
+  }
+}
+
+impl Settings {
+  pub fn path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("rusty-invaders").join(SETTINGS_FILE_NAME))
+  }
+
+  /// Reads the saved settings, falling back to the defaults when there are none or they can't be
+  /// read.
+  pub fn load() -> Self {
+    storage::read_or_default::<Settings>(Settings::path()).clamped()
+  }
+
+  pub fn save(&self) -> Result<(), String> {
+    let path = Settings::path().ok_or("no user config directory")?;
+    storage::write(&path, self)
+  }
+
+  // a hand edited file shouldn't be able to blow up the speakers
+  fn clamped(mut self) -> Self {
+    let audio = &mut self.audio;
+    for volume in [
+      &mut audio.master,
+      &mut audio.music,
+      &mut audio.sfx,
+      &mut audio.ui,
+    ] {
+      *volume = volume.clamp(0.0, 1.0);
+    }
+
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn volumes_out_of_range_are_clamped() {
+    let mut settings = Settings::default();
+    settings.audio.master = 4.0;
+    settings.audio.music = -1.0;
+
+    let settings = settings.clamped();
+    assert_eq!(settings.audio.master, 1.0);
+    assert_eq!(settings.audio.music, 0.0);
+    assert_eq!(settings.audio.sfx, 1.0);
+  }
+}
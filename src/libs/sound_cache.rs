@@ -0,0 +1,77 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::path::PathBuf;
+
+use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+
+use super::assets::AssetError;
+
+/// Decoded sounds from one directory, each read from disk the first time it is asked for. A file
+/// that can't be read is logged once and never tried again.
+///
+/// Needs no audio device, cloning the data it hands out only copies a pointer to the samples.
+pub struct SoundCache<K: Copy + Eq + Hash + Debug> {
+  path: PathBuf,
+  sounds: HashMap<K, StaticSoundData>,
+  broken: HashSet<K>,
+}
+
+impl<K: Copy + Eq + Hash + Debug> SoundCache<K> {
+  pub fn new(path: PathBuf) -> Self {
+    Self {
+      path,
+      sounds: HashMap::new(),
+      broken: HashSet::new(),
+    }
+  }
+
+  /// The sound in `file_name` decoded, stored under `key` from then on.
+  pub fn load(&mut self, key: K, file_name: &str) -> Option<&StaticSoundData> {
+    if self.broken.contains(&key) {
+      return None;
+    }
+
+    if !self.sounds.contains_key(&key) {
+      let path = self.path.join(file_name);
+      match StaticSoundData::from_file(&path, StaticSoundSettings::default()) {
+        Ok(data) => {
+          self.sounds.insert(key, data);
+        }
+        Err(err) => {
+          let err = AssetError::sound(&path, err);
+          log::warn!("{}, {:?} stays silent", err, key);
+          self.broken.insert(key);
+          return None;
+        }
+      }
+    }
+
+    self.sounds.get(&key)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  // nothing outside the tests themselves, the sounds bench compiles this module without them
+  #[test]
+  fn sounds_are_decoded_once_and_kept() {
+    let assets = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+    let mut cache = super::SoundCache::new(assets.join("sounds"));
+
+    assert!(cache.load("hit", "hit.wav").is_some());
+    assert_eq!(cache.sounds.len(), 1);
+    assert!(cache.load("hit", "hit.wav").is_some());
+    assert_eq!(cache.sounds.len(), 1);
+  }
+
+  #[test]
+  fn missing_files_are_not_tried_again() {
+    let assets = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+    let mut cache = super::SoundCache::new(assets.join("sounds"));
+
+    assert!(cache.load("missing", "missing.wav").is_none());
+    assert!(cache.broken.contains("missing"));
+    assert!(cache.load("missing", "hit.wav").is_none());
+  }
+}
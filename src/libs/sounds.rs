@@ -1,59 +1,257 @@
 use super::assets::*;
-use std::path::PathBuf;
+use super::audio::AudioBackend;
+use super::config::{config, EffectConfig};
+use super::mixer::{Bus, Mixer};
+use super::music::{Music, MusicPlayer};
+use super::settings::AudioSettings;
+use super::sound_cache::SoundCache;
+use super::voices::Voices;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
 
 use kira::{
-  manager::{backend::DefaultBackend, AudioManager, AudioManagerSettings},
-  sound::static_sound::{StaticSoundData, StaticSoundSettings},
-  tween::{Tween, Value},
-  Volume,
+  sound::{
+    static_sound::{StaticSoundHandle, StaticSoundSettings},
+    PlaybackState,
+  },
+  tween::Tween,
 };
+use rand::Rng;
 
+// how far apart the screen edges sound, 1 would put them entirely in one ear
+const STEREO_WIDTH: f64 = 0.8;
+
+// a stolen voice fades this fast instead of clicking
+const STEAL_FADE: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SoundEffect {
   PlayerExplosion,
   PlayerHit,
   PlayerLaserShoot,
   EnemyExplosion,
+  Saucer,
+  SaucerExplosion,
+  MenuSelect,
 }
 
 impl SoundEffect {
+  pub const ALL: [SoundEffect; 7] = [
+    SoundEffect::PlayerExplosion,
+    SoundEffect::PlayerHit,
+    SoundEffect::PlayerLaserShoot,
+    SoundEffect::EnemyExplosion,
+    SoundEffect::Saucer,
+    SoundEffect::SaucerExplosion,
+    SoundEffect::MenuSelect,
+  ];
+
   pub fn as_filename(&self) -> &'static str {
     match self {
       SoundEffect::PlayerExplosion => PLAYER_EXPLOSION_SOUND_NAME,
       SoundEffect::PlayerHit => PLAYER_HIT_SOUND_NAME,
       SoundEffect::PlayerLaserShoot => PLAYER_LASER_SHOOT_SOUND_NAME,
       SoundEffect::EnemyExplosion => ENEMY_EXPLOSION_SOUND_NAME,
+      SoundEffect::Saucer => SAUCER_SOUND_NAME,
+      SoundEffect::SaucerExplosion => ENEMY_EXPLOSION_SOUND_NAME,
+      SoundEffect::MenuSelect => MENU_SELECT_SOUND_NAME,
+    }
+  }
+
+  /// How loud the effect plays and how many copies of it can overlap, from the `sounds` section
+  /// of the config.
+  pub fn config(&self) -> &'static EffectConfig {
+    let sounds = &config().sounds;
+    match self {
+      SoundEffect::PlayerExplosion => &sounds.player_explosion,
+      SoundEffect::PlayerHit => &sounds.player_hit,
+      SoundEffect::PlayerLaserShoot => &sounds.player_laser_shoot,
+      SoundEffect::EnemyExplosion => &sounds.enemy_explosion,
+      SoundEffect::Saucer => &sounds.saucer,
+      SoundEffect::SaucerExplosion => &sounds.saucer_explosion,
+      SoundEffect::MenuSelect => &sounds.menu_select,
+    }
+  }
+
+  pub fn bus(&self) -> Bus {
+    match self {
+      SoundEffect::MenuSelect => Bus::Ui,
+      _ => Bus::Sfx,
     }
   }
 }
 
+/// The audio on kira, playing the effects and the music on the default output device. A missing
+/// file is logged once and stays silent, the game never stops over audio.
+///
+/// Effects are decoded once and kept, cloning the data only copies a pointer to the samples.
 pub struct SoundsManager {
-  sounds_path: PathBuf,
-  manager: AudioManager,
+  mixer: Mixer,
+  settings: AudioSettings,
+  cache: SoundCache<SoundEffect>,
+  music: MusicPlayer,
+  loops: HashMap<SoundEffect, StaticSoundHandle>,
+  voices: Voices<SoundEffect, StaticSoundHandle>,
 }
 
 impl SoundsManager {
-  pub fn new(assets: &PathBuf) -> Self {
-    let manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default()).unwrap();
+  /// Fails when there is no audio device to play on.
+  pub fn new(assets: &Path, settings: &AudioSettings) -> Result<Self, AssetError> {
+    let mut sounds = Self {
+      mixer: Mixer::new(settings)?,
+      settings: settings.clone(),
+      cache: SoundCache::new(assets.join("sounds")),
+      music: MusicPlayer::new(assets),
+      loops: HashMap::new(),
+      voices: Voices::new(),
+    };
 
-    Self {
-      sounds_path: assets.join("sounds"),
-      manager,
+    for sound_effect in SoundEffect::ALL {
+      sounds.cache.load(sound_effect, sound_effect.as_filename());
     }
+
+    Ok(sounds)
   }
 
-  pub fn play(&mut self, sound_effect: SoundEffect, volume: impl Into<Value<Volume>>) {
-    let path = self.sounds_path.clone();
-    let sound_data = StaticSoundData::from_file(
-      path.join(sound_effect.as_filename()),
-      StaticSoundSettings::default(),
-    )
-    .unwrap();
+  /// Kira's panning for a horizontal position, 0 is hard left, 0.5 centred and 1 hard right.
+  fn panning(x: Option<f64>) -> f64 {
+    match x {
+      Some(x) => {
+        let offset = (x / config().window.width).clamp(0.0, 1.0) - 0.5;
+        0.5 + offset * STEREO_WIDTH
+      }
+      None => 0.5,
+    }
+  }
 
+  fn start(
+    &mut self,
+    sound_effect: SoundEffect,
+    settings: StaticSoundSettings,
+  ) -> Option<StaticSoundHandle> {
+    let data = self
+      .cache
+      .load(sound_effect, sound_effect.as_filename())?
+      .with_settings(settings);
+
+    match self.mixer.play(sound_effect.bus(), data) {
+      Ok(handle) => Some(handle),
+      // running out of voices or a busy audio thread only costs this one sound
+      Err(err) => {
+        log::debug!("{}", err);
+        None
+      }
+    }
+  }
+}
+
+impl AudioBackend for SoundsManager {
+  /// A game effect triggered right after the previous one of its kind is dropped. Menu sounds
+  /// never are: the game clock stands still while paused, so pausing and resuming straight away
+  /// would swallow the second one. An effect at its `max_voices` stops its oldest voice to make
+  /// room.
+  fn play(&mut self, sound_effect: SoundEffect, x: Option<f64>, now: f64) {
+    if sound_effect.bus() == Bus::Sfx && self.voices.merges(sound_effect, now) {
+      return;
+    }
+
+    let mut settings = StaticSoundSettings::new()
+      .volume(sound_effect.config().volume)
+      .panning(SoundsManager::panning(x));
+
+    // the same sample over and over sounds mechanical, menu blips are meant to stay the same
+    let variation = config().sounds.pitch_variation;
+    if variation > 0.0 && sound_effect.bus() == Bus::Sfx {
+      let rate = 1.0 + rand::thread_rng().gen_range(-variation..=variation);
+      settings = settings.playback_rate(rate);
+    }
+
+    let max_voices = sound_effect.config().max_voices;
+    let stolen = self.voices.make_room(sound_effect, max_voices, |handle| {
+      matches!(
+        handle.state(),
+        PlaybackState::Stopping | PlaybackState::Stopped
+      )
+    });
+    for mut handle in stolen {
+      let _ = handle.stop(Tween {
+        duration: STEAL_FADE,
+        ..Default::default()
+      });
+    }
+
+    if let Some(handle) = self.start(sound_effect, settings) {
+      self.voices.start(sound_effect, handle, now);
+    }
+  }
+
+  fn play_loop(&mut self, sound_effect: SoundEffect, x: Option<f64>) {
+    if self.loops.contains_key(&sound_effect) {
+      return;
+    }
+
+    let settings = StaticSoundSettings::new()
+      .loop_region(..)
+      .volume(sound_effect.config().volume)
+      .panning(SoundsManager::panning(x));
+    if let Some(handle) = self.start(sound_effect, settings) {
+      self.loops.insert(sound_effect, handle);
+    }
+  }
+
+  fn pan_loop(&mut self, sound_effect: SoundEffect, x: f64) {
+    if let Some(handle) = self.loops.get_mut(&sound_effect) {
+      let _ = handle.set_panning(SoundsManager::panning(Some(x)), Tween::default());
+    }
+  }
+
+  fn stop_loop(&mut self, sound_effect: SoundEffect) {
+    if let Some(mut handle) = self.loops.remove(&sound_effect) {
+      // the sound is gone either way, there is nothing left to do if the audio thread is
+      let _ = handle.stop(Tween::default());
+    }
+  }
+
+  fn play_music(&mut self, music: Music) {
+    self.music.play(&mut self.mixer, music);
+  }
+
+  fn update_music(&mut self, dt: f64, enemies_left: usize, enemies_total: usize) {
     self
-      .manager
-      .play(sound_data)
-      .unwrap()
-      .set_volume(volume, Tween::default())
-      .unwrap();
+      .music
+      .update(&mut self.mixer, dt, enemies_left, enemies_total);
+  }
+
+  fn pause_music(&mut self, paused: bool) {
+    self.music.set_paused(paused);
+  }
+
+  fn settings(&self) -> &AudioSettings {
+    &self.settings
+  }
+
+  fn toggle_mute(&mut self) {
+    self.settings.muted = !self.settings.muted;
+    self.mixer.apply(&self.settings);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn panning_follows_the_emitter_short_of_the_edges() {
+    let width = config().window.width;
+
+    assert_eq!(SoundsManager::panning(None), 0.5);
+    assert_eq!(SoundsManager::panning(Some(width / 2.0)), 0.5);
+    assert!(SoundsManager::panning(Some(0.0)) > 0.0);
+    assert!(SoundsManager::panning(Some(width)) < 1.0);
+    assert_eq!(
+      SoundsManager::panning(Some(-width)),
+      SoundsManager::panning(Some(0.0))
+    );
   }
 }
@@ -0,0 +1,91 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use graphics::types::Rectangle;
+
+/// A uniform grid over the playfield that buckets things by the cells their bounds touch, so a
+/// collision check only has to look at what shares a cell instead of at everything.
+///
+/// Rectangles are `[x, y, width, height]`, edges count as touching like they do for colliders.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid<K> {
+  cell_size: f64,
+  cells: HashMap<(i64, i64), Vec<K>>,
+}
+
+impl<K: Copy + Eq + Hash> SpatialGrid<K> {
+  pub fn new(cell_size: f64) -> Self {
+    Self {
+      cell_size,
+      cells: HashMap::new(),
+    }
+  }
+
+  pub fn clear(&mut self) {
+    for bucket in self.cells.values_mut() {
+      bucket.clear();
+    }
+  }
+
+  pub fn insert(&mut self, key: K, rect: Rectangle<f64>) {
+    for cell in self.cells_of(rect) {
+      self.cells.entry(cell).or_default().push(key);
+    }
+  }
+
+  /// Empties the grid and fills it back with `entries`, for things that moved since last time.
+  pub fn rebuild(&mut self, entries: impl IntoIterator<Item = (K, Rectangle<f64>)>) {
+    self.clear();
+    for (key, rect) in entries {
+      self.insert(key, rect);
+    }
+  }
+
+  /// Everything sharing a cell with `rect`, each key once, in a stable order. These are only
+  /// candidates, the caller still runs the exact check on them.
+  pub fn query(&self, rect: Rectangle<f64>) -> Vec<K> {
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+
+    for cell in self.cells_of(rect) {
+      if let Some(bucket) = self.cells.get(&cell) {
+        for key in bucket {
+          if seen.insert(*key) {
+            found.push(*key);
+          }
+        }
+      }
+    }
+
+    found
+  }
+
+  fn cells_of(&self, rect: Rectangle<f64>) -> impl Iterator<Item = (i64, i64)> {
+    let [x, y, width, height] = rect;
+    let cell = |value: f64| (value / self.cell_size).floor() as i64;
+    let (left, right) = (cell(x), cell(x + width));
+    let (top, bottom) = (cell(y), cell(y + height));
+
+    (top..=bottom).flat_map(move |row| (left..=right).map(move |col| (col, row)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  // no glob import, the collision bench compiles this module without running its tests
+  #[test]
+  fn queries_return_each_neighbour_once() {
+    let mut grid = super::SpatialGrid::new(10.0);
+    // spans four cells
+    grid.insert(1, [5.0, 5.0, 10.0, 10.0]);
+    grid.insert(2, [50.0, 50.0, 5.0, 5.0]);
+
+    assert_eq!(grid.query([0.0, 0.0, 20.0, 20.0]), vec![1]);
+    assert_eq!(grid.query([52.0, 52.0, 1.0, 1.0]), vec![2]);
+    assert!(grid.query([30.0, 30.0, 5.0, 5.0]).is_empty());
+
+    grid.rebuild([(3, [30.0, 30.0, 5.0, 5.0])]);
+    assert_eq!(grid.query([30.0, 30.0, 5.0, 5.0]), vec![3]);
+    assert!(grid.query([0.0, 0.0, 20.0, 20.0]).is_empty());
+  }
+}
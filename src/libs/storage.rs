@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ron::ser::PrettyConfig;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// A file the game writes in a RON layout of its own, with a version in it so an older or newer
+/// layout is refused instead of misread.
+pub trait Versioned: Serialize + DeserializeOwned {
+  /// Bumped whenever the layout of the file changes.
+  const VERSION: u32;
+  /// What the file holds, for error messages.
+  const NAME: &'static str;
+
+  fn version(&self) -> u32;
+}
+
+/// Reads `path`, refusing a file written in another layout.
+pub fn read<T: Versioned>(path: &Path) -> Result<T, String> {
+  let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+  let stored: T = ron::from_str(&contents).map_err(|err| err.to_string())?;
+
+  if stored.version() != T::VERSION {
+    return Err(format!(
+      "unsupported {} version {} (expected {})",
+      T::NAME,
+      stored.version(),
+      T::VERSION
+    ));
+  }
+
+  Ok(stored)
+}
+
+/// Reads `path` if there is one, starting from the default when the file is missing or can't be
+/// used.
+pub fn read_or_default<T: Versioned + Default>(path: Option<PathBuf>) -> T {
+  let path = match path {
+    Some(path) if path.exists() => path,
+    _ => return T::default(),
+  };
+
+  read(&path).unwrap_or_else(|err| {
+    log::warn!("{}: {}, ignoring it", path.display(), err);
+    T::default()
+  })
+}
+
+/// Writes `stored` to `path`, creating its directory if needed.
+pub fn write<T: Versioned>(path: &Path, stored: &T) -> Result<(), String> {
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+  }
+
+  let contents =
+    ron::ser::to_string_pretty(stored, PrettyConfig::default()).map_err(|err| err.to_string())?;
+  fs::write(path, contents).map_err(|err| err.to_string())
+}
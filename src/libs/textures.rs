@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::Path;
 
 use opengl_graphics::{Texture, TextureSettings};
 
@@ -8,6 +8,7 @@ pub struct TextureLoader {
   pub player: Option<Texture>,
   pub bullet: Option<Texture>,
   pub background: Option<Texture>,
+  pub saucer: Option<Texture>,
   pub enemies: EnemiesTextures,
 }
 
@@ -18,45 +19,32 @@ pub struct EnemiesTextures {
 }
 
 impl TextureLoader {
-  pub fn new(assets_folder: &PathBuf) -> Self {
+  pub fn new(assets_folder: &Path) -> Self {
     let textures = assets_folder.join("images");
     let textures_settings = TextureSettings::new();
 
     Self {
-      player: TextureLoader::handle(Texture::from_path(
-        textures.join(PLAYER_TEXTURE_NAME),
-        &textures_settings,
-      )),
-      bullet: TextureLoader::handle(Texture::from_path(
-        textures.join(BULLET_TEXTURE_NAME),
-        &textures_settings,
-      )),
-      background: TextureLoader::handle(Texture::from_path(
-        textures.join(BACKGROUND_TEXTURE_NAME),
-        &textures_settings,
-      )),
+      player: TextureLoader::load(&textures.join(PLAYER_TEXTURE_NAME), &textures_settings),
+      bullet: TextureLoader::load(&textures.join(BULLET_TEXTURE_NAME), &textures_settings),
+      background: TextureLoader::load(&textures.join(BACKGROUND_TEXTURE_NAME), &textures_settings),
+      saucer: TextureLoader::load(&textures.join(SAUCER_TEXTURE_NAME), &textures_settings),
 
       enemies: EnemiesTextures {
-        crab: TextureLoader::handle(Texture::from_path(
-          textures.join(CRAB_TEXTURE_NAME),
-          &textures_settings,
-        )),
-        squid: TextureLoader::handle(Texture::from_path(
-          textures.join(SQUID_TEXTURE_NAME),
-          &textures_settings,
-        )),
-        octopus: TextureLoader::handle(Texture::from_path(
-          textures.join(OCTOPUS_TEXTURE_NAME),
-          &textures_settings,
-        )),
+        crab: TextureLoader::load(&textures.join(CRAB_TEXTURE_NAME), &textures_settings),
+        squid: TextureLoader::load(&textures.join(SQUID_TEXTURE_NAME), &textures_settings),
+        octopus: TextureLoader::load(&textures.join(OCTOPUS_TEXTURE_NAME), &textures_settings),
       },
     }
   }
 
-  fn handle(tex: Result<Texture, String>) -> Option<Texture> {
-    match tex {
-      Ok(texture) => Some(texture),
-      Err(_) => None,
+  /// A missing sprite is only a warning, entities without a texture draw a placeholder.
+  fn load(path: &Path, settings: &TextureSettings) -> Option<Texture> {
+    match image::open(path).map_err(|err| AssetError::image(path, err)) {
+      Ok(image) => Some(Texture::from_image(&image.to_rgba8(), settings)),
+      Err(err) => {
+        log::warn!("{}", err);
+        None
+      }
     }
   }
 }
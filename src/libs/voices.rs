@@ -0,0 +1,102 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+// triggers of the same effect closer than this many game seconds are heard as one anyway, only
+// the first plays
+const MERGE_WINDOW: f64 = 0.015;
+
+/// Which copies of every effect are still playing and when each one last started, so a burst of
+/// identical triggers neither piles up nor clips.
+///
+/// Knows nothing about the audio device, a voice is whatever the caller uses to stop a sound.
+pub struct Voices<K, V> {
+  /// Oldest first.
+  playing: HashMap<K, VecDeque<V>>,
+  /// Game time each effect last started at.
+  last_started: HashMap<K, f64>,
+}
+
+impl<K: Copy + Eq + Hash, V> Default for Voices<K, V> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<K: Copy + Eq + Hash, V> Voices<K, V> {
+  pub fn new() -> Self {
+    Self {
+      playing: HashMap::new(),
+      last_started: HashMap::new(),
+    }
+  }
+
+  /// Whether a trigger of `key` at game time `now` lands too close to the last one to be told
+  /// apart. A clock started over puts the last one in the future, which doesn't count.
+  pub fn merges(&self, key: K, now: f64) -> bool {
+    self
+      .last_started
+      .get(&key)
+      .is_some_and(|last| (0.0..MERGE_WINDOW).contains(&(now - last)))
+  }
+
+  /// Forgets the voices `finished` says are over, then hands back the oldest ones that have to
+  /// stop for one more to fit under `max_voices`.
+  pub fn make_room(&mut self, key: K, max_voices: usize, finished: impl Fn(&V) -> bool) -> Vec<V> {
+    let voices = match self.playing.get_mut(&key) {
+      Some(voices) => voices,
+      None => return Vec::new(),
+    };
+
+    voices.retain(|voice| !finished(voice));
+
+    let excess = (voices.len() + 1).saturating_sub(max_voices);
+    voices.drain(..excess).collect()
+  }
+
+  /// Keeps track of `voice`, which just started at game time `now`.
+  pub fn start(&mut self, key: K, voice: V, now: f64) {
+    self.playing.entry(key).or_default().push_back(voice);
+    self.last_started.insert(key, now);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn the_oldest_voices_make_room_for_a_new_one() {
+    let mut voices = Voices::new();
+    for id in 0..3 {
+      voices.start("explosion", id, id as f64);
+    }
+
+    assert_eq!(voices.make_room("explosion", 3, |_| false), vec![0]);
+    assert_eq!(voices.make_room("explosion", 1, |_| false), vec![1, 2]);
+    assert!(voices.make_room("laser", 1, |_| false).is_empty());
+  }
+
+  #[test]
+  fn finished_voices_are_not_stolen() {
+    let mut voices = Voices::new();
+    for id in 0..3 {
+      voices.start("explosion", id, id as f64);
+    }
+
+    assert!(voices.make_room("explosion", 3, |id| *id == 1).is_empty());
+    assert_eq!(voices.make_room("explosion", 2, |_| false), vec![0]);
+  }
+
+  #[test]
+  fn triggers_right_after_one_another_merge() {
+    let mut voices = Voices::new();
+    voices.start("explosion", 0, 1.0);
+
+    assert!(voices.merges("explosion", 1.0));
+    assert!(voices.merges("explosion", 1.01));
+    assert!(!voices.merges("explosion", 1.02));
+    assert!(!voices.merges("laser", 1.0));
+    // the clock started over
+    assert!(!voices.merges("explosion", 0.0));
+  }
+}
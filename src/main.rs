@@ -9,34 +9,55 @@ mod game;
 mod libs;
 
 use std::env::current_dir;
+use std::path::PathBuf;
+use std::process;
+use std::time::Instant;
 
 use components::*;
 use game::*;
-use libs::config::{FONT_NAME, WINDOW_HEIGHT, WINDOW_NAME, WINDOW_WIDTH};
+use libs::assets;
+use libs::audio;
+use libs::cli::Args;
+use libs::config::{config, Config, CONFIG_FILE_NAME, FONT_NAME, WINDOW_NAME};
+use libs::gamepads::{GamepadEvent, Gamepads};
+use libs::settings::Settings;
 
-use opengl_graphics::{GlGraphics, GlyphCache, OpenGL, TextureSettings};
+use opengl_graphics::{GlGraphics, OpenGL};
 use piston::event_loop::{EventSettings, Events};
-use piston::input::{RenderEvent, UpdateEvent};
+use piston::input::RenderEvent;
 use piston::window::WindowSettings;
-use piston::{ButtonEvent, ButtonState, EventLoop};
+use piston::{ButtonEvent, ButtonState, FocusEvent, TextEvent};
 
 use piston_window::PistonWindow as Window;
 
-// #[derive(Copy, Clone, Hash, PartialEq, Eq)]
-// enum Music {
-//   MainTheme,
-// }
-
 // #[derive(Copy, Clone, Hash, PartialEq, Eq)]
 // enum Sound {}
 
 fn main() {
   clearscreen::clear().expect("failed to clear screen");
 
+  env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+  let args = Args::parse();
+  load_config(&args);
+
+  let playback = args.replay.as_ref().map(|path| {
+    Replay::load(path).unwrap_or_else(|err| {
+      log::error!("{}: {}", path.display(), err);
+      process::exit(1);
+    })
+  });
+  let seed = match &playback {
+    Some(replay) => replay.seed,
+    None => args.seed.unwrap_or_else(rand::random),
+  };
+  println!("seed: {}", seed);
+
   let opengl = OpenGL::V3_2;
   let assets = current_dir().unwrap().join("assets");
   let font = assets.join("fonts").join(FONT_NAME);
-  let mut window: Window = WindowSettings::new(WINDOW_NAME, [WINDOW_WIDTH, WINDOW_HEIGHT])
+  let window_size = [config().window.width, config().window.height];
+  let mut window: Window = WindowSettings::new(WINDOW_NAME, window_size)
     .graphics_api(opengl)
     .exit_on_esc(true)
     .fullscreen(false)
@@ -46,23 +67,86 @@ fn main() {
     .build()
     .unwrap();
 
-  window.set_lazy(true);
+  let settings = Settings::load();
+  let sounds = audio::open(&assets, &settings.audio, !args.no_audio);
 
   let mut game_manager = GameManager::start(
     GlGraphics::new(opengl),
-    GlyphCache::new(font, (), TextureSettings::new()).unwrap(),
+    assets::load_font(&font),
     &assets,
+    seed,
+    settings,
+    sounds,
   );
-  let mut events = Events::new(EventSettings::new()).ups(60);
+  if let Some(replay) = playback {
+    game_manager.play_back(replay);
+  }
+  if let Some(path) = &args.record {
+    game_manager.record_to(path);
+  }
+
+  let mut gamepads = Gamepads::new();
+  let mut events = Events::new(EventSettings::new());
+  let mut last_frame = Instant::now();
 
   while let Some(e) = events.next(&mut window) {
-    e.update(|args| game_manager.update(args));
-    e.render(|args| game_manager.render(args));
+    for event in gamepads.poll() {
+      match event {
+        GamepadEvent::Button(args) => match args.state {
+          ButtonState::Press => game_manager.on_press(&args),
+          ButtonState::Release => game_manager.on_release(&args),
+        },
+        GamepadEvent::Axis(args) => game_manager.on_axis(&args),
+        GamepadEvent::Disconnected(pad) => game_manager.on_disconnect(pad),
+      }
+    }
+
+    e.render(|args| {
+      let now = Instant::now();
+      game_manager.update(now.duration_since(last_frame).as_secs_f64());
+      last_frame = now;
+
+      game_manager.render(args);
+    });
 
     if let Some(keys) = e.button_args() {
-      if keys.state == ButtonState::Press {
-        game_manager.on_press(&keys);
+      match keys.state {
+        ButtonState::Press => game_manager.on_press(&keys),
+        ButtonState::Release => game_manager.on_release(&keys),
       }
     }
+
+    if let Some(focused) = e.focus_args() {
+      game_manager.on_focus(focused);
+    }
+
+    if let Some(text) = e.text_args() {
+      game_manager.on_text(&text);
+    }
+  }
+}
+
+/// Installs the config file given with `--config`, or `config.ron` next to the game when present.
+fn load_config(args: &Args) {
+  let path = match &args.config {
+    Some(path) => path.clone(),
+    None => PathBuf::from(CONFIG_FILE_NAME),
+  };
+
+  if args.config.is_none() && !path.exists() {
+    return;
+  }
+
+  match Config::load(&path) {
+    Ok((config, unknown_keys)) => {
+      for key in unknown_keys {
+        log::warn!("{}: unknown key `{}` ignored", path.display(), key);
+      }
+      config.install();
+    }
+    Err(err) => {
+      log::error!("{}: {}", path.display(), err);
+      process::exit(1);
+    }
   }
 }